@@ -1,9 +1,101 @@
+use std::{env, fs, path::PathBuf};
+
+const ENCODER_FILES: &[&str] = &[
+    "basisu_astc_decomp.cpp",
+    "basisu_backend.cpp",
+    "basisu_basis_file.cpp",
+    "basisu_bc7enc.cpp",
+    "basisu_enc.cpp",
+    "basisu_etc.cpp",
+    "basisu_frontend.cpp",
+    "basisu_global_selector_palette_helpers.cpp",
+    "basisu_gpu_texture.cpp",
+    "basisu_kernels_sse.cpp",
+    "basisu_pvrtc1_4.cpp",
+    "basisu_resample_filters.cpp",
+    "basisu_resampler.cpp",
+    "basisu_ssim.cpp",
+    "basisu_uastc_enc.cpp",
+    "jpgd.cpp",
+    "lodepng.cpp",
+];
+
+/// Hooks added to `basis_compressor::process()` in `basisu_comp.cpp`, so encodes can report progress and
+/// be cancelled. Each call site in `process()` gets a call to `basisrs_encoder_stage` in front of it (see
+/// `src/basisrs_encoder_hooks.h`), which makes `process()` fail once the compression is cancelled.
+///
+/// The patched copy is built instead of the original. If basis_universal changes one of these call sites,
+/// the build fails rather than silently losing the hook.
+const ENCODER_HOOKS: &[(&str, &str)] = &[
+    (
+        "!read_source_images()",
+        "!basisrs_encoder_stage(cBASISRSPhasePreparingImages) || !read_source_images()",
+    ),
+    (
+        "!process_frontend()",
+        "!basisrs_encoder_stage(cBASISRSPhaseGeneratingCodebooks) || !process_frontend()",
+    ),
+    (
+        "!extract_frontend_texture_data()",
+        "!basisrs_encoder_stage(cBASISRSPhaseEncodingSlices) || !extract_frontend_texture_data()",
+    ),
+    (
+        "!process_backend()",
+        "!basisrs_encoder_stage(cBASISRSPhaseRdo) || !process_backend()",
+    ),
+    (
+        "= encode_slices_to_uastc();",
+        "= basisrs_encoder_stage(cBASISRSPhaseEncodingSlices) ? encode_slices_to_uastc() : cECFailedFrontEnd;",
+    ),
+    (
+        "!create_basis_file_and_transcode()",
+        "!basisrs_encoder_stage(cBASISRSPhaseWritingFile) || !create_basis_file_and_transcode()",
+    ),
+];
+
+/// Writes `basisu_comp.cpp` with [`ENCODER_HOOKS`] applied to `OUT_DIR`, and returns its path.
+fn patch_encoder() -> PathBuf {
+    let source = fs::read_to_string("basisu/encoder/basisu_comp.cpp").expect("failed to read basisu_comp.cpp");
+
+    let mut patched = String::from("#include \"basisrs_encoder_hooks.h\"\n");
+    patched.push_str(&source);
+
+    for (call, hooked) in ENCODER_HOOKS {
+        assert_eq!(
+            patched.matches(call).count(),
+            1,
+            "basisu_comp.cpp should call `{}` exactly once, update ENCODER_HOOKS",
+            call
+        );
+        patched = patched.replace(call, hooked);
+    }
+
+    let path = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("basisu_comp.cpp");
+    fs::write(&path, patched).expect("failed to write patched basisu_comp.cpp");
+    path
+}
+
 fn main() {
+    // C sources: single-file Zstandard for supercompressed KTX2 files, and the encoder's BMP loader.
+    // This is the full library rather than zstddeclib.c, as the encoder needs to compress as well.
+    cc::Build::new()
+        .files(&["basisu/zstd/zstd.c", "basisu/encoder/apg_bmp.c"])
+        .compile("basisu_c");
+
     let mut build = cc::Build::new();
 
     build
         .files(&["basisu/transcoder/basisu_transcoder.cpp", "src/basisrs_interface.cpp"])
+        .files(ENCODER_FILES.iter().map(|file| format!("basisu/encoder/{}", file)))
+        .file(patch_encoder())
+        // The patched basisu_comp.cpp finds its own headers and basisrs_encoder_hooks.h through these.
+        .include("basisu/encoder")
+        .include("src")
         .cpp(true)
+        .define("BASISD_SUPPORT_KTX2", "1")
+        .define("BASISD_SUPPORT_KTX2_ZSTD", "1")
+        // The SSE kernels need to be built with SSE4.1 enabled, which we can't assume.
+        .define("BASISU_SUPPORT_SSE", "0")
         .flag_if_supported("-std=c++14")
         .flag_if_supported("/std:c++14");
 
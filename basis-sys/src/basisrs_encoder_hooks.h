@@ -0,0 +1,21 @@
+#pragma once
+
+// Shared by basisrs_interface.cpp and the copy of basisu_comp.cpp that build.rs patches, which calls
+// basisrs_encoder_stage as each stage of basis_compressor::process() starts.
+
+// Stages of basisrs_compressor_process, in the order they run.
+enum basisrs_compression_phase {
+    // Generating mipmaps and splitting the images into blocks.
+    cBASISRSPhasePreparingImages,
+    // ETC1S only: clustering endpoints and selectors into codebooks.
+    cBASISRSPhaseGeneratingCodebooks,
+    // Encoding the blocks of each slice. UASTC RDO runs here, slice by slice.
+    cBASISRSPhaseEncodingSlices,
+    // ETC1S only: endpoint and selector RDO, then entropy coding.
+    cBASISRSPhaseRdo,
+    // Assembling the output files and transcoding them back to validate them and compute stats.
+    cBASISRSPhaseWritingFile,
+};
+
+// Returns false once the compression running on this thread should stop, which makes process() fail.
+extern "C" bool basisrs_encoder_stage(basisrs_compression_phase phase);
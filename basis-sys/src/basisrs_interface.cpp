@@ -1,8 +1,36 @@
 #define BASISRS_INTERFACE_HAS_STRUCTS
 #include "basisrs_interface.hpp"
 
+#include <algorithm>
+#include <thread>
+
 static basist::etc1_global_selector_codebook *g_pGlobal_codebook;
 
+// Names of the resampler filters selectable through basisrs_compressor_params::mip_filter.
+static const char *g_mip_filters[] = {"box", "tent", "kaiser", "lanczos4", "mitchell"};
+static const uint32_t g_total_mip_filters = sizeof(g_mip_filters) / sizeof(g_mip_filters[0]);
+
+// The flag is shared with Rust's AtomicBool, which is a single byte.
+static_assert(sizeof(std::atomic<bool>) == 1 && ATOMIC_BOOL_LOCK_FREE == 2, "std::atomic<bool> must match AtomicBool");
+
+// Progress reporting for the compression running on this thread, if any.
+struct basisrs_progress {
+    basisrs_progress_callback callback;
+    void *user_data;
+    const std::atomic<bool> *cancelled;
+    bool stopped;
+};
+
+static thread_local basisrs_progress *t_progress;
+
+struct basisrs_compressor {
+    basisu::job_pool job_pool;
+    basisu::basis_compressor compressor;
+    basisu::vector<basisu::image> source_images;
+
+    basisrs_compressor() : job_pool(std::max(1u, std::thread::hardware_concurrency())) {}
+};
+
 extern "C" {
     void basisrs_init() {
         basisu_transcoder_init();
@@ -12,6 +40,10 @@ extern "C" {
         }
     }
 
+    void basisrs_init_encoder() {
+        basisu::basisu_encoder_init();
+    }
+
     void basisrs_deinit() {
         if (g_pGlobal_codebook) {
             delete g_pGlobal_codebook;
@@ -41,6 +73,62 @@ extern "C" {
         delete me;
     }
 
+    basisu_transcoder_state *basisrs_create_transcoder_state() {
+        return new basisu_transcoder_state();
+    }
+
+    void basisrs_destroy_transcoder_state(basisu_transcoder_state *me) {
+        delete me;
+    }
+
+    basisu_lowlevel_etc1s_transcoder *basisrs_create_global_codebooks(const void *pData, uint32_t data_size) {
+        basisu_transcoder transcoder(g_pGlobal_codebook);
+        if (!transcoder.validate_header(pData, data_size) ||
+            transcoder.get_tex_format(pData, data_size) != basis_tex_format::cETC1S) {
+            return nullptr;
+        }
+
+        const basis_file_header *pHeader = static_cast<const basis_file_header *>(pData);
+        const uint8_t *pBytes = static_cast<const uint8_t *>(pData);
+
+        if (pHeader->m_flags & cBASISHeaderFlagUsesGlobalCodebook) {
+            return nullptr;
+        }
+
+        // The header CRC doesn't cover what it points to, so check every section lies within the file,
+        // as basisu_transcoder::start_transcoding does.
+        auto in_file = [data_size](uint32_t ofs, uint32_t size) {
+            return size != 0 && ofs <= data_size && size <= data_size - ofs;
+        };
+        if (!in_file(pHeader->m_endpoint_cb_file_ofs, pHeader->m_endpoint_cb_file_size) ||
+            !in_file(pHeader->m_selector_cb_file_ofs, pHeader->m_selector_cb_file_size) ||
+            !in_file(pHeader->m_tables_file_ofs, pHeader->m_tables_file_size)) {
+            return nullptr;
+        }
+
+        basisu_lowlevel_etc1s_transcoder *codebooks = new basisu_lowlevel_etc1s_transcoder(g_pGlobal_codebook);
+
+        bool success = codebooks->decode_palettes(
+                pHeader->m_total_endpoints, pBytes + pHeader->m_endpoint_cb_file_ofs, pHeader->m_endpoint_cb_file_size,
+                pHeader->m_total_selectors, pBytes + pHeader->m_selector_cb_file_ofs, pHeader->m_selector_cb_file_size);
+        success = success && codebooks->decode_tables(pBytes + pHeader->m_tables_file_ofs, pHeader->m_tables_file_size);
+
+        if (!success) {
+            delete codebooks;
+            return nullptr;
+        }
+
+        return codebooks;
+    }
+
+    void basisrs_destroy_global_codebooks(basisu_lowlevel_etc1s_transcoder *me) {
+        delete me;
+    }
+
+    void basisrs_set_global_codebooks(basisu_transcoder *me, const basisu_lowlevel_etc1s_transcoder *codebooks) {
+        me->set_global_codebooks(codebooks);
+    }
+
     bool basisrs_validate_file_checksums(const basisu_transcoder *me, const void *pData, uint32_t data_size,
                                          bool full_validation) {
         return me->validate_file_checksums(pData, data_size, full_validation);
@@ -135,4 +223,248 @@ extern "C" {
                                       basisu_image_level_info &level_info, uint32_t image_index, uint32_t level_index) {
         return me->get_image_level_info(pData, data_size, level_info, image_index, level_index);
     }
+
+    ktx2_transcoder *basisrs_create_ktx2_transcoder() {
+        return new ktx2_transcoder(g_pGlobal_codebook);
+    }
+
+    void basisrs_destroy_ktx2_transcoder(ktx2_transcoder *me) {
+        delete me;
+    }
+
+    bool basisrs_ktx2_init(ktx2_transcoder *me, const void *pData, uint32_t data_size) {
+        return me->init(pData, data_size);
+    }
+
+    uint32_t basisrs_ktx2_get_width(const ktx2_transcoder *me) {
+        return me->get_width();
+    }
+
+    uint32_t basisrs_ktx2_get_height(const ktx2_transcoder *me) {
+        return me->get_height();
+    }
+
+    uint32_t basisrs_ktx2_get_levels(const ktx2_transcoder *me) {
+        return me->get_levels();
+    }
+
+    uint32_t basisrs_ktx2_get_layers(const ktx2_transcoder *me) {
+        return me->get_layers();
+    }
+
+    uint32_t basisrs_ktx2_get_faces(const ktx2_transcoder *me) {
+        return me->get_faces();
+    }
+
+    basis_tex_format basisrs_ktx2_get_format(const ktx2_transcoder *me) {
+        return me->get_format();
+    }
+
+    bool basisrs_ktx2_get_has_alpha(const ktx2_transcoder *me) {
+        return me->get_has_alpha();
+    }
+
+    bool basisrs_ktx2_is_video(const ktx2_transcoder *me) {
+        return me->is_video();
+    }
+
+    uint32_t basisrs_ktx2_get_dfd_color_model(const ktx2_transcoder *me) {
+        return me->get_dfd_color_model();
+    }
+
+    uint32_t basisrs_ktx2_get_dfd_color_primaries(const ktx2_transcoder *me) {
+        return static_cast<uint32_t>(me->get_dfd_color_primaries());
+    }
+
+    uint32_t basisrs_ktx2_get_dfd_transfer_func(const ktx2_transcoder *me) {
+        return me->get_dfd_transfer_func();
+    }
+
+    uint32_t basisrs_ktx2_get_dfd_flags(const ktx2_transcoder *me) {
+        return me->get_dfd_flags();
+    }
+
+    uint32_t basisrs_ktx2_get_dfd_total_samples(const ktx2_transcoder *me) {
+        return me->get_dfd_total_samples();
+    }
+
+    uint32_t basisrs_ktx2_get_dfd_channel_id0(const ktx2_transcoder *me) {
+        return me->get_dfd_channel_id0();
+    }
+
+    uint32_t basisrs_ktx2_get_dfd_channel_id1(const ktx2_transcoder *me) {
+        return me->get_dfd_channel_id1();
+    }
+
+    uint32_t basisrs_ktx2_get_total_key_values(const ktx2_transcoder *me) {
+        return me->get_key_values().size();
+    }
+
+    bool basisrs_ktx2_get_key_value(const ktx2_transcoder *me, uint32_t index, basisrs_vector_u8 &key,
+                                    basisrs_vector_u8 &value) {
+        const auto &key_values = me->get_key_values();
+        if (index >= key_values.size()) {
+            return false;
+        }
+
+        key = basisrs_vector_u8{
+                key_values[index].m_key.data(),
+                key_values[index].m_key.size(),
+        };
+        value = basisrs_vector_u8{
+                key_values[index].m_value.data(),
+                key_values[index].m_value.size(),
+        };
+        return true;
+    }
+
+    bool basisrs_ktx2_get_image_level_info(const ktx2_transcoder *me, ktx2_image_level_info &level_info,
+                                           uint32_t level_index, uint32_t layer_index, uint32_t face_index) {
+        return me->get_image_level_info(level_info, level_index, layer_index, face_index);
+    }
+
+    bool basisrs_ktx2_start_transcoding(ktx2_transcoder *me) {
+        return me->start_transcoding();
+    }
+
+    bool basisrs_ktx2_transcode_image_level(ktx2_transcoder *me, uint32_t level_index, uint32_t layer_index,
+                                            uint32_t face_index, void *pOutput_blocks,
+                                            uint32_t output_blocks_buf_size_in_blocks_or_pixels,
+                                            transcoder_texture_format fmt, uint32_t decode_flags,
+                                            uint32_t output_row_pitch_in_blocks_or_pixels,
+                                            uint32_t output_rows_in_pixels) {
+        return me->transcode_image_level(level_index, layer_index, face_index, pOutput_blocks,
+                                         output_blocks_buf_size_in_blocks_or_pixels, fmt, decode_flags,
+                                         output_row_pitch_in_blocks_or_pixels, output_rows_in_pixels);
+    }
+
+    basisrs_compressor *basisrs_create_compressor() {
+        return new basisrs_compressor();
+    }
+
+    void basisrs_destroy_compressor(basisrs_compressor *me) {
+        delete me;
+    }
+
+    void basisrs_compressor_add_source_image(basisrs_compressor *me, const basisrs_source_image &image) {
+        basisu::image img(image.width, image.height);
+        memcpy(img.get_ptr(), image.pixels, static_cast<size_t>(image.width) * image.height * 4);
+        me->source_images.push_back(img);
+    }
+
+    int basisrs_compressor_process(basisrs_compressor *me, const basisrs_compressor_params &params,
+                                   basisrs_progress_callback progress, void *user_data,
+                                   const std::atomic<bool> *cancelled) {
+        basisu::basis_compressor_params comp_params;
+
+        comp_params.m_source_images.swap(me->source_images);
+
+        comp_params.m_uastc = params.uastc;
+        comp_params.m_quality_level = params.quality_level;
+        comp_params.m_pack_uastc_flags = params.uastc_level;
+        comp_params.m_perceptual = params.perceptual;
+        comp_params.m_y_flip = params.y_flip;
+        comp_params.m_userdata0 = params.userdata0;
+        comp_params.m_userdata1 = params.userdata1;
+        comp_params.m_tex_type = params.tex_type;
+        comp_params.m_us_per_frame = params.us_per_frame;
+
+        comp_params.m_rdo_uastc = params.rdo_uastc;
+        comp_params.m_rdo_uastc_quality_scalar = params.rdo_uastc_lambda;
+        comp_params.m_rdo_uastc_dict_size = params.rdo_uastc_dict_size;
+
+        comp_params.m_create_ktx2_file = params.create_ktx2;
+        comp_params.m_ktx2_srgb_transfer_func = params.srgb;
+        comp_params.m_ktx2_uastc_supercompression = params.ktx2_zstd ? basist::KTX2_SS_ZSTANDARD : basist::KTX2_SS_NONE;
+        comp_params.m_ktx2_zstd_supercompression_level = params.ktx2_zstd_level;
+
+        comp_params.m_compute_stats = params.compute_stats;
+
+        comp_params.m_mip_gen = params.mip_gen;
+        comp_params.m_mip_filter = g_mip_filters[std::min<uint32_t>(params.mip_filter, g_total_mip_filters - 1)];
+        comp_params.m_mip_srgb = params.mip_srgb;
+        comp_params.m_mip_wrapping = params.mip_wrapping;
+        comp_params.m_mip_renormalize = params.mip_renormalize;
+        comp_params.m_mip_smallest_dimension = params.mip_smallest_dimension;
+
+        comp_params.m_pGlobal_codebooks = params.global_codebooks;
+
+        comp_params.m_read_source_images = false;
+        comp_params.m_write_output_basis_files = false;
+        comp_params.m_status_output = false;
+        comp_params.m_multithreading = true;
+        comp_params.m_pSel_codebook = g_pGlobal_codebook;
+        comp_params.m_pJob_pool = &me->job_pool;
+
+        if (!me->compressor.init(comp_params)) {
+            return -1;
+        }
+
+        // process() runs its stages on this thread, calling basisrs_encoder_stage as each one starts.
+        basisrs_progress progress_state{progress, user_data, cancelled, false};
+        t_progress = &progress_state;
+        basisu::basis_compressor::error_code error = me->compressor.process();
+        t_progress = nullptr;
+
+        if (progress_state.stopped) {
+            return -2;
+        }
+
+        return error;
+    }
+
+    bool basisrs_encoder_stage(basisrs_compression_phase phase) {
+        basisrs_progress *progress = t_progress;
+        if (!progress) {
+            return true;
+        }
+
+        if ((progress->cancelled && progress->cancelled->load(std::memory_order_acquire)) ||
+            (progress->callback && !progress->callback(progress->user_data, phase))) {
+            progress->stopped = true;
+        }
+
+        return !progress->stopped;
+    }
+
+    basisrs_vector_u8 basisrs_compressor_get_output_basis_file(const basisrs_compressor *me) {
+        const basisu::uint8_vec &output = me->compressor.get_output_basis_file();
+        return basisrs_vector_u8{
+                output.data(),
+                output.size(),
+        };
+    }
+
+    uint32_t basisrs_compressor_get_total_stats(const basisrs_compressor *me) {
+        return me->compressor.get_stats().size();
+    }
+
+    bool basisrs_compressor_get_stats(const basisrs_compressor *me, uint32_t index, basisrs_image_stats &stats) {
+        const auto &all_stats = me->compressor.get_stats();
+        if (index >= all_stats.size()) {
+            return false;
+        }
+
+        const basisu::image_stats &image_stats = all_stats[index];
+        stats.width = image_stats.m_width;
+        stats.height = image_stats.m_height;
+        stats.rgb_psnr = image_stats.m_basis_rgb_avg_psnr;
+        stats.rgba_psnr = image_stats.m_basis_rgba_avg_psnr;
+        stats.alpha_psnr = image_stats.m_basis_a_avg_psnr;
+        stats.luma_709_psnr = image_stats.m_basis_luma_709_psnr;
+        stats.luma_709_ssim = image_stats.m_basis_luma_709_ssim;
+        return true;
+    }
+
+    float basisrs_compressor_get_bits_per_texel(const basisrs_compressor *me) {
+        return me->compressor.get_basis_bits_per_texel();
+    }
+
+    basisrs_vector_u8 basisrs_compressor_get_output_ktx2_file(const basisrs_compressor *me) {
+        const basisu::uint8_vec &output = me->compressor.get_output_ktx2_file();
+        return basisrs_vector_u8{
+                output.data(),
+                output.size(),
+        };
+    }
 }
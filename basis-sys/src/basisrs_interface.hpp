@@ -1,11 +1,17 @@
 #pragma once
 
+#include <atomic>
 #include <cstdint>
 #include <vector>
 
+#include "basisrs_encoder_hooks.h"
+
 #ifndef BASISRS_INTERFACE_HAS_STRUCTS
 struct basisu_transcoder_state;
 struct basisu_transcoder;
+struct ktx2_transcoder;
+struct basisrs_compressor;
+struct basisu_lowlevel_etc1s_transcoder;
 
 // Low-level formats directly supported by the transcoder (other supported texture formats are combinations of these low-level block formats).
 // You probably don't care about these enum's unless you are going pretty low-level and calling the transcoder to decode individual slices.
@@ -143,6 +149,28 @@ enum class transcoder_texture_format
     cTFATC_RGBA_INTERPOLATED_ALPHA = cTFATC_RGBA,
 };
 
+// Flags passed to the transcode functions' decode_flags parameter.
+enum basisu_decode_flags
+{
+    // PVRTC1: decode non-pow2 ETC1S texture level to the next larger power of 2 (not implemented yet, but we're going to support it). Ignored if the slice's dimensions are already a power of 2.
+    cDecodeFlagsPVRTCDecodeToNextPow2 = 2,
+
+    // When decoding to an opaque texture format, if the basis file has alpha, decode the alpha slice instead of the color slice to the output texture format.
+    // This is primarily to allow decoding of textures with alpha to multiple ETC1 textures (one for color, another for alpha).
+    cDecodeFlagsTranscodeAlphaDataToOpaqueFormats = 4,
+
+    // Forbid usage of BC1 3 color blocks (we don't support BC1 punchthrough alpha yet).
+    // This flag is used internally when decoding to BC3.
+    cDecodeFlagsBC1ForbidThreeColorBlocks = 8,
+
+    // The output buffer contains alpha endpoint/selector indices.
+    // Used internally when decoding formats like ASTC that require both color and alpha data to be available when transcoding to the output format.
+    cDecodeFlagsOutputHasAlphaIndices = 16,
+
+    // Use the slower, higher quality UASTC transcoding paths (BC7, ETC1/2, BC1/3, PVRTC1).
+    cDecodeFlagsHighQuality = 32
+};
+
 struct basisu_slice_info
 {
     uint32_t m_orig_width;
@@ -241,13 +269,39 @@ struct basisu_file_info
     bool m_etc1s;					// true if the file is ETC1S
     bool m_has_alpha_slices;	// true if the texture has alpha slices (for ETC1S: even slices RGB, odd slices alpha)
 };
+
+struct ktx2_image_level_info
+{
+    uint32_t m_level_index;
+    uint32_t m_layer_index;
+    uint32_t m_face_index;
+
+    uint32_t m_orig_width;
+    uint32_t m_orig_height;
+
+    uint32_t m_width;
+    uint32_t m_height;
+
+    uint32_t m_num_blocks_x;
+    uint32_t m_num_blocks_y;
+    uint32_t m_total_blocks;
+
+    bool m_alpha_flag;		// true if the image has alpha data
+    bool m_iframe_flag;		// true if the image is an I-Frame
+};
 #else
 #include "../basisu/transcoder/basisu_transcoder.h"
+#include "../basisu/encoder/basisu_comp.h"
 using namespace basist;
+
+// Owns a basis_compressor and the job pool it encodes with.
+struct basisrs_compressor;
 #endif
 
 extern "C" {
     void basisrs_init();
+    // Sets up the encoder's global tables. Only needed before compressing, after basisrs_init.
+    void basisrs_init_encoder();
     void basisrs_deinit();
 
     struct basisrs_vector_u32 {
@@ -266,6 +320,20 @@ extern "C" {
     basisu_transcoder* basisrs_create_transcoder();
     void basisrs_destroy_transcoder(basisu_transcoder* me);
 
+    // Per-thread scratch state for the transcoder. Required to transcode with a single transcoder from multiple threads,
+    // and carries the previous frame's data forward when decoding ETC1S video.
+    basisu_transcoder_state* basisrs_create_transcoder_state();
+    void basisrs_destroy_transcoder_state(basisu_transcoder_state* me);
+
+    // Decodes the ETC1S codebooks of a .basis file, to share with files encoded against them.
+    // Returns null if the file isn't ETC1S or has no codebooks of its own.
+    basisu_lowlevel_etc1s_transcoder* basisrs_create_global_codebooks(const void *pData, uint32_t data_size);
+    void basisrs_destroy_global_codebooks(basisu_lowlevel_etc1s_transcoder* me);
+
+    // Codebooks used by files encoded against global codebooks. Must outlive the transcoder, and be set before
+    // start_transcoding.
+    void basisrs_set_global_codebooks(basisu_transcoder *me, const basisu_lowlevel_etc1s_transcoder *codebooks);
+
     // Validates the .basis file. This computes a crc16 over the entire file, so it's slow.
     bool basisrs_validate_file_checksums(const basisu_transcoder *me, const void *pData, uint32_t data_size,
                                          bool full_validation);
@@ -352,4 +420,138 @@ extern "C" {
                                  basisu_transcoder_state *pState = nullptr, void *pAlpha_blocks = nullptr,
                                  uint32_t output_rows_in_pixels = 0, int channel0 = -1, int channel1 = -1);
 
+
+    struct basisrs_vector_u8 {
+        const uint8_t *values;
+        size_t size;
+    };
+
+    ktx2_transcoder* basisrs_create_ktx2_transcoder();
+    void basisrs_destroy_ktx2_transcoder(ktx2_transcoder* me);
+
+    // Parses the .ktx2 header, level index, DFD and key/value data. The file must outlive the transcoder, or the next init.
+    bool basisrs_ktx2_init(ktx2_transcoder *me, const void *pData, uint32_t data_size);
+
+    uint32_t basisrs_ktx2_get_width(const ktx2_transcoder *me);
+    uint32_t basisrs_ktx2_get_height(const ktx2_transcoder *me);
+    uint32_t basisrs_ktx2_get_levels(const ktx2_transcoder *me);
+    // 0 if the texture isn't an array.
+    uint32_t basisrs_ktx2_get_layers(const ktx2_transcoder *me);
+    // 1 or 6 (cubemap).
+    uint32_t basisrs_ktx2_get_faces(const ktx2_transcoder *me);
+    basis_tex_format basisrs_ktx2_get_format(const ktx2_transcoder *me);
+    bool basisrs_ktx2_get_has_alpha(const ktx2_transcoder *me);
+    bool basisrs_ktx2_is_video(const ktx2_transcoder *me);
+
+    uint32_t basisrs_ktx2_get_dfd_color_model(const ktx2_transcoder *me);
+    uint32_t basisrs_ktx2_get_dfd_color_primaries(const ktx2_transcoder *me);
+    uint32_t basisrs_ktx2_get_dfd_transfer_func(const ktx2_transcoder *me);
+    uint32_t basisrs_ktx2_get_dfd_flags(const ktx2_transcoder *me);
+    uint32_t basisrs_ktx2_get_dfd_total_samples(const ktx2_transcoder *me);
+    uint32_t basisrs_ktx2_get_dfd_channel_id0(const ktx2_transcoder *me);
+    uint32_t basisrs_ktx2_get_dfd_channel_id1(const ktx2_transcoder *me);
+
+    uint32_t basisrs_ktx2_get_total_key_values(const ktx2_transcoder *me);
+    // The key includes its null terminator. Both vectors point into the transcoder and are valid until the next init.
+    bool basisrs_ktx2_get_key_value(const ktx2_transcoder *me, uint32_t index, basisrs_vector_u8 &key,
+                                    basisrs_vector_u8 &value);
+
+    bool basisrs_ktx2_get_image_level_info(const ktx2_transcoder *me, ktx2_image_level_info &level_info,
+                                           uint32_t level_index, uint32_t layer_index, uint32_t face_index);
+
+    // Must be called before transcoding. For ETC1S files, this decompresses the global codebooks.
+    bool basisrs_ktx2_start_transcoding(ktx2_transcoder *me);
+
+    // Same parameters as basisrs_transcode_image_level, with the image index replaced by the layer and face.
+    // Always uses the transcoder's internal state.
+    bool basisrs_ktx2_transcode_image_level(ktx2_transcoder *me, uint32_t level_index, uint32_t layer_index,
+                                            uint32_t face_index, void *pOutput_blocks,
+                                            uint32_t output_blocks_buf_size_in_blocks_or_pixels,
+                                            transcoder_texture_format fmt, uint32_t decode_flags = 0,
+                                            uint32_t output_row_pitch_in_blocks_or_pixels = 0,
+                                            uint32_t output_rows_in_pixels = 0);
+
+
+    // Tightly packed RGBA8 pixels.
+    struct basisrs_source_image {
+        const uint8_t *pixels;
+        uint32_t width;
+        uint32_t height;
+    };
+
+    struct basisrs_compressor_params {
+        bool uastc;
+        // ETC1S quality, [1, 255].
+        uint32_t quality_level;
+        // UASTC pack level, [0, 4] (cPackUASTCLevelFastest to cPackUASTCLevelVerySlow).
+        uint32_t uastc_level;
+        bool perceptual;
+        bool srgb;
+        bool y_flip;
+        uint32_t userdata0;
+        uint32_t userdata1;
+        basis_texture_type tex_type;
+        // Only used by cBASISTexTypeVideoFrames.
+        uint32_t us_per_frame;
+        // UASTC rate-distortion optimization. Lower lambdas are higher quality, larger dictionaries find more
+        // matches but are slower.
+        bool rdo_uastc;
+        float rdo_uastc_lambda;
+        uint32_t rdo_uastc_dict_size;
+        bool create_ktx2;
+        // Zstandard supercompression of UASTC KTX2 files. ETC1S KTX2 files are always BasisLZ.
+        bool ktx2_zstd;
+        int ktx2_zstd_level;
+        // Transcode the output after encoding to measure its quality.
+        bool compute_stats;
+        bool mip_gen;
+        // Index into box, tent, kaiser, lanczos4, mitchell.
+        uint32_t mip_filter;
+        bool mip_srgb;
+        bool mip_wrapping;
+        bool mip_renormalize;
+        // Stop generating levels once both dimensions are at most this.
+        int mip_smallest_dimension;
+        // ETC1S codebooks to encode against instead of generating new ones, or null.
+        const basisu_lowlevel_etc1s_transcoder *global_codebooks;
+    };
+
+    // Quality of a single image level, measured against the source.
+    struct basisrs_image_stats {
+        uint32_t width;
+        uint32_t height;
+        float rgb_psnr;
+        float rgba_psnr;
+        float alpha_psnr;
+        float luma_709_psnr;
+        float luma_709_ssim;
+    };
+
+    // Called on the encoding thread as each stage starts. Returning false stops the compression.
+    typedef bool (*basisrs_progress_callback)(void *user_data, basisrs_compression_phase phase);
+
+    basisrs_compressor* basisrs_create_compressor();
+    void basisrs_destroy_compressor(basisrs_compressor* me);
+
+    // Copies the image into the compressor, to be encoded by the next basisrs_compressor_process.
+    void basisrs_compressor_add_source_image(basisrs_compressor *me, const basisrs_source_image &image);
+
+    // Encodes every image added since the last call. progress may be null.
+    // cancelled may be null, and is polled from the encoding thread between stages, so another thread can set it.
+    // Returns -1 if the parameters were rejected, -2 if cancelled, otherwise a basis_compressor::error_code (0 is success).
+    int basisrs_compressor_process(basisrs_compressor *me, const basisrs_compressor_params &params,
+                                   basisrs_progress_callback progress, void *user_data,
+                                   const std::atomic<bool> *cancelled);
+
+    // Valid until the compressor is destroyed or used again.
+    basisrs_vector_u8 basisrs_compressor_get_output_basis_file(const basisrs_compressor *me);
+    // One entry per image level, ordered by image then level. Empty unless compute_stats was set.
+    uint32_t basisrs_compressor_get_total_stats(const basisrs_compressor *me);
+    bool basisrs_compressor_get_stats(const basisrs_compressor *me, uint32_t index, basisrs_image_stats &stats);
+
+    float basisrs_compressor_get_bits_per_texel(const basisrs_compressor *me);
+
+    // Empty unless create_ktx2 was set. Valid until the compressor is destroyed or used again.
+    basisrs_vector_u8 basisrs_compressor_get_output_ktx2_file(const basisrs_compressor *me);
+
 }
\ No newline at end of file
@@ -11,6 +11,21 @@ pub struct basisu_transcoder_state {
 pub struct basisu_transcoder {
     _unused: [u8; 0],
 }
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ktx2_transcoder {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct basisrs_compressor {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct basisu_lowlevel_etc1s_transcoder {
+    _unused: [u8; 0],
+}
 pub const block_format_cETC1: block_format = 0;
 pub const block_format_cETC2_RGBA: block_format = 1;
 pub const block_format_cBC1: block_format = 2;
@@ -89,6 +104,12 @@ pub const transcoder_texture_format_cTFBC7_ALT: transcoder_texture_format = 7;
 pub const transcoder_texture_format_cTFASTC_4x4: transcoder_texture_format = 10;
 pub const transcoder_texture_format_cTFATC_RGBA_INTERPOLATED_ALPHA: transcoder_texture_format = 12;
 pub type transcoder_texture_format = ::std::os::raw::c_int;
+pub const basisu_decode_flags_cDecodeFlagsPVRTCDecodeToNextPow2: basisu_decode_flags = 2;
+pub const basisu_decode_flags_cDecodeFlagsTranscodeAlphaDataToOpaqueFormats: basisu_decode_flags = 4;
+pub const basisu_decode_flags_cDecodeFlagsBC1ForbidThreeColorBlocks: basisu_decode_flags = 8;
+pub const basisu_decode_flags_cDecodeFlagsOutputHasAlphaIndices: basisu_decode_flags = 16;
+pub const basisu_decode_flags_cDecodeFlagsHighQuality: basisu_decode_flags = 32;
+pub type basisu_decode_flags = ::std::os::raw::c_uint;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct basisu_slice_info {
@@ -783,9 +804,161 @@ fn bindgen_test_layout_basisu_file_info() {
         )
     );
 }
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct ktx2_image_level_info {
+    pub m_level_index: u32,
+    pub m_layer_index: u32,
+    pub m_face_index: u32,
+    pub m_orig_width: u32,
+    pub m_orig_height: u32,
+    pub m_width: u32,
+    pub m_height: u32,
+    pub m_num_blocks_x: u32,
+    pub m_num_blocks_y: u32,
+    pub m_total_blocks: u32,
+    pub m_alpha_flag: bool,
+    pub m_iframe_flag: bool,
+}
+#[test]
+fn bindgen_test_layout_ktx2_image_level_info() {
+    assert_eq!(
+        ::std::mem::size_of::<ktx2_image_level_info>(),
+        44usize,
+        concat!("Size of: ", stringify!(ktx2_image_level_info))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<ktx2_image_level_info>(),
+        4usize,
+        concat!("Alignment of ", stringify!(ktx2_image_level_info))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_level_index as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_level_index)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_layer_index as *const _ as usize },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_layer_index)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_face_index as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_face_index)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_orig_width as *const _ as usize },
+        12usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_orig_width)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_orig_height as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_orig_height)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_width as *const _ as usize },
+        20usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_width)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_height as *const _ as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_height)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_num_blocks_x as *const _ as usize },
+        28usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_num_blocks_x)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_num_blocks_y as *const _ as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_num_blocks_y)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_total_blocks as *const _ as usize },
+        36usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_total_blocks)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_alpha_flag as *const _ as usize },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_alpha_flag)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<ktx2_image_level_info>())).m_iframe_flag as *const _ as usize },
+        41usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(ktx2_image_level_info),
+            "::",
+            stringify!(m_iframe_flag)
+        )
+    );
+}
 extern "C" {
     pub fn basisrs_init();
 }
+extern "C" {
+    pub fn basisrs_init_encoder();
+}
 extern "C" {
     pub fn basisrs_deinit();
 }
@@ -879,6 +1052,24 @@ extern "C" {
 extern "C" {
     pub fn basisrs_destroy_transcoder(me: *mut basisu_transcoder);
 }
+extern "C" {
+    pub fn basisrs_create_transcoder_state() -> *mut basisu_transcoder_state;
+}
+extern "C" {
+    pub fn basisrs_destroy_transcoder_state(me: *mut basisu_transcoder_state);
+}
+extern "C" {
+    pub fn basisrs_create_global_codebooks(
+        pData: *const ::std::os::raw::c_void,
+        data_size: u32,
+    ) -> *mut basisu_lowlevel_etc1s_transcoder;
+}
+extern "C" {
+    pub fn basisrs_destroy_global_codebooks(me: *mut basisu_lowlevel_etc1s_transcoder);
+}
+extern "C" {
+    pub fn basisrs_set_global_codebooks(me: *mut basisu_transcoder, codebooks: *const basisu_lowlevel_etc1s_transcoder);
+}
 extern "C" {
     pub fn basisrs_validate_file_checksums(
         me: *const basisu_transcoder,
@@ -1029,3 +1220,604 @@ extern "C" {
         channel1: ::std::os::raw::c_int,
     ) -> bool;
 }
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct basisrs_vector_u8 {
+    pub values: *const u8,
+    pub size: size_t,
+}
+#[test]
+fn bindgen_test_layout_basisrs_vector_u8() {
+    assert_eq!(
+        ::std::mem::size_of::<basisrs_vector_u8>(),
+        16usize,
+        concat!("Size of: ", stringify!(basisrs_vector_u8))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<basisrs_vector_u8>(),
+        8usize,
+        concat!("Alignment of ", stringify!(basisrs_vector_u8))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_vector_u8>())).values as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_vector_u8),
+            "::",
+            stringify!(values)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_vector_u8>())).size as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_vector_u8),
+            "::",
+            stringify!(size)
+        )
+    );
+}
+extern "C" {
+    pub fn basisrs_create_ktx2_transcoder() -> *mut ktx2_transcoder;
+}
+extern "C" {
+    pub fn basisrs_destroy_ktx2_transcoder(me: *mut ktx2_transcoder);
+}
+extern "C" {
+    pub fn basisrs_ktx2_init(me: *mut ktx2_transcoder, pData: *const ::std::os::raw::c_void, data_size: u32) -> bool;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_width(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_height(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_levels(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_layers(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_faces(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_format(me: *const ktx2_transcoder) -> basis_tex_format;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_has_alpha(me: *const ktx2_transcoder) -> bool;
+}
+extern "C" {
+    pub fn basisrs_ktx2_is_video(me: *const ktx2_transcoder) -> bool;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_dfd_color_model(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_dfd_color_primaries(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_dfd_transfer_func(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_dfd_flags(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_dfd_total_samples(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_dfd_channel_id0(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_dfd_channel_id1(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_total_key_values(me: *const ktx2_transcoder) -> u32;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_key_value(
+        me: *const ktx2_transcoder,
+        index: u32,
+        key: *mut basisrs_vector_u8,
+        value: *mut basisrs_vector_u8,
+    ) -> bool;
+}
+extern "C" {
+    pub fn basisrs_ktx2_get_image_level_info(
+        me: *const ktx2_transcoder,
+        level_info: *mut ktx2_image_level_info,
+        level_index: u32,
+        layer_index: u32,
+        face_index: u32,
+    ) -> bool;
+}
+extern "C" {
+    pub fn basisrs_ktx2_start_transcoding(me: *mut ktx2_transcoder) -> bool;
+}
+extern "C" {
+    pub fn basisrs_ktx2_transcode_image_level(
+        me: *mut ktx2_transcoder,
+        level_index: u32,
+        layer_index: u32,
+        face_index: u32,
+        pOutput_blocks: *mut ::std::os::raw::c_void,
+        output_blocks_buf_size_in_blocks_or_pixels: u32,
+        fmt: transcoder_texture_format,
+        decode_flags: u32,
+        output_row_pitch_in_blocks_or_pixels: u32,
+        output_rows_in_pixels: u32,
+    ) -> bool;
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct basisrs_source_image {
+    pub pixels: *const u8,
+    pub width: u32,
+    pub height: u32,
+}
+#[test]
+fn bindgen_test_layout_basisrs_source_image() {
+    assert_eq!(
+        ::std::mem::size_of::<basisrs_source_image>(),
+        16usize,
+        concat!("Size of: ", stringify!(basisrs_source_image))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<basisrs_source_image>(),
+        8usize,
+        concat!("Alignment of ", stringify!(basisrs_source_image))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_source_image>())).pixels as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_source_image),
+            "::",
+            stringify!(pixels)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_source_image>())).width as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_source_image),
+            "::",
+            stringify!(width)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_source_image>())).height as *const _ as usize },
+        12usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_source_image),
+            "::",
+            stringify!(height)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct basisrs_compressor_params {
+    pub uastc: bool,
+    pub quality_level: u32,
+    pub uastc_level: u32,
+    pub perceptual: bool,
+    pub srgb: bool,
+    pub y_flip: bool,
+    pub userdata0: u32,
+    pub userdata1: u32,
+    pub tex_type: basis_texture_type,
+    pub us_per_frame: u32,
+    pub rdo_uastc: bool,
+    pub rdo_uastc_lambda: f32,
+    pub rdo_uastc_dict_size: u32,
+    pub create_ktx2: bool,
+    pub ktx2_zstd: bool,
+    pub ktx2_zstd_level: ::std::os::raw::c_int,
+    pub compute_stats: bool,
+    pub mip_gen: bool,
+    pub mip_filter: u32,
+    pub mip_srgb: bool,
+    pub mip_wrapping: bool,
+    pub mip_renormalize: bool,
+    pub mip_smallest_dimension: ::std::os::raw::c_int,
+    pub global_codebooks: *const basisu_lowlevel_etc1s_transcoder,
+}
+#[test]
+fn bindgen_test_layout_basisrs_compressor_params() {
+    assert_eq!(
+        ::std::mem::size_of::<basisrs_compressor_params>(),
+        80usize,
+        concat!("Size of: ", stringify!(basisrs_compressor_params))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<basisrs_compressor_params>(),
+        8usize,
+        concat!("Alignment of ", stringify!(basisrs_compressor_params))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).uastc as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(uastc)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).quality_level as *const _ as usize },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(quality_level)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).uastc_level as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(uastc_level)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).perceptual as *const _ as usize },
+        12usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(perceptual)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).srgb as *const _ as usize },
+        13usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(srgb)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).y_flip as *const _ as usize },
+        14usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(y_flip)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).userdata0 as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(userdata0)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).userdata1 as *const _ as usize },
+        20usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(userdata1)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).tex_type as *const _ as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(tex_type)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).us_per_frame as *const _ as usize },
+        28usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(us_per_frame)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).rdo_uastc as *const _ as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(rdo_uastc)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).rdo_uastc_lambda as *const _ as usize },
+        36usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(rdo_uastc_lambda)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).rdo_uastc_dict_size as *const _ as usize },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(rdo_uastc_dict_size)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).create_ktx2 as *const _ as usize },
+        44usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(create_ktx2)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).ktx2_zstd as *const _ as usize },
+        45usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(ktx2_zstd)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).ktx2_zstd_level as *const _ as usize },
+        48usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(ktx2_zstd_level)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).compute_stats as *const _ as usize },
+        52usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(compute_stats)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).mip_gen as *const _ as usize },
+        53usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(mip_gen)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).mip_filter as *const _ as usize },
+        56usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(mip_filter)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).mip_srgb as *const _ as usize },
+        60usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(mip_srgb)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).mip_wrapping as *const _ as usize },
+        61usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(mip_wrapping)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).mip_renormalize as *const _ as usize },
+        62usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(mip_renormalize)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).mip_smallest_dimension as *const _ as usize },
+        64usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(mip_smallest_dimension)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_compressor_params>())).global_codebooks as *const _ as usize },
+        72usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_compressor_params),
+            "::",
+            stringify!(global_codebooks)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct basisrs_image_stats {
+    pub width: u32,
+    pub height: u32,
+    pub rgb_psnr: f32,
+    pub rgba_psnr: f32,
+    pub alpha_psnr: f32,
+    pub luma_709_psnr: f32,
+    pub luma_709_ssim: f32,
+}
+#[test]
+fn bindgen_test_layout_basisrs_image_stats() {
+    assert_eq!(
+        ::std::mem::size_of::<basisrs_image_stats>(),
+        28usize,
+        concat!("Size of: ", stringify!(basisrs_image_stats))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<basisrs_image_stats>(),
+        4usize,
+        concat!("Alignment of ", stringify!(basisrs_image_stats))
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_image_stats>())).width as *const _ as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_image_stats),
+            "::",
+            stringify!(width)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_image_stats>())).height as *const _ as usize },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_image_stats),
+            "::",
+            stringify!(height)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_image_stats>())).rgb_psnr as *const _ as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_image_stats),
+            "::",
+            stringify!(rgb_psnr)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_image_stats>())).rgba_psnr as *const _ as usize },
+        12usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_image_stats),
+            "::",
+            stringify!(rgba_psnr)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_image_stats>())).alpha_psnr as *const _ as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_image_stats),
+            "::",
+            stringify!(alpha_psnr)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_image_stats>())).luma_709_psnr as *const _ as usize },
+        20usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_image_stats),
+            "::",
+            stringify!(luma_709_psnr)
+        )
+    );
+    assert_eq!(
+        unsafe { &(*(::std::ptr::null::<basisrs_image_stats>())).luma_709_ssim as *const _ as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(basisrs_image_stats),
+            "::",
+            stringify!(luma_709_ssim)
+        )
+    );
+}
+pub const basisrs_compression_phase_cBASISRSPhasePreparingImages: basisrs_compression_phase = 0;
+pub const basisrs_compression_phase_cBASISRSPhaseGeneratingCodebooks: basisrs_compression_phase = 1;
+pub const basisrs_compression_phase_cBASISRSPhaseEncodingSlices: basisrs_compression_phase = 2;
+pub const basisrs_compression_phase_cBASISRSPhaseRdo: basisrs_compression_phase = 3;
+pub const basisrs_compression_phase_cBASISRSPhaseWritingFile: basisrs_compression_phase = 4;
+pub type basisrs_compression_phase = ::std::os::raw::c_int;
+pub type basisrs_progress_callback = ::std::option::Option<
+    unsafe extern "C" fn(user_data: *mut ::std::os::raw::c_void, phase: basisrs_compression_phase) -> bool,
+>;
+extern "C" {
+    pub fn basisrs_create_compressor() -> *mut basisrs_compressor;
+}
+extern "C" {
+    pub fn basisrs_destroy_compressor(me: *mut basisrs_compressor);
+}
+extern "C" {
+    pub fn basisrs_compressor_add_source_image(me: *mut basisrs_compressor, image: *const basisrs_source_image);
+}
+extern "C" {
+    pub fn basisrs_compressor_process(
+        me: *mut basisrs_compressor,
+        params: *const basisrs_compressor_params,
+        progress: basisrs_progress_callback,
+        user_data: *mut ::std::os::raw::c_void,
+        cancelled: *const ::std::sync::atomic::AtomicBool,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn basisrs_compressor_get_output_basis_file(me: *const basisrs_compressor) -> basisrs_vector_u8;
+}
+extern "C" {
+    pub fn basisrs_compressor_get_total_stats(me: *const basisrs_compressor) -> u32;
+}
+extern "C" {
+    pub fn basisrs_compressor_get_stats(
+        me: *const basisrs_compressor,
+        index: u32,
+        stats: *mut basisrs_image_stats,
+    ) -> bool;
+}
+extern "C" {
+    pub fn basisrs_compressor_get_bits_per_texel(me: *const basisrs_compressor) -> f32;
+}
+extern "C" {
+    pub fn basisrs_compressor_get_output_ktx2_file(me: *const basisrs_compressor) -> basisrs_vector_u8;
+}
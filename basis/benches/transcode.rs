@@ -22,7 +22,11 @@ pub fn inner(c: &mut Criterion, path: &str) {
             (image_info.orig_width * image_info.orig_height) as u64,
         ));
         group.bench_function(format!("{:?} => {:?}", basis, format), |b| {
-            b.iter(|| prepared.transcode_image_level(0, 0, format).unwrap())
+            b.iter(|| {
+                prepared
+                    .transcode_image_level(0, 0, format, basis::DecodeFlags::empty())
+                    .unwrap()
+            })
         });
     }
 
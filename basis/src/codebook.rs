@@ -0,0 +1,85 @@
+use crate::{
+    init, sys, validate_slice_length, BasisError, BasisTextureFormat, Compressor, Container, SourceImage, TextureType,
+    Transcoder,
+};
+
+/// ETC1S endpoint and selector codebooks shared by a set of textures.
+///
+/// Small ETC1S files are mostly codebooks. Encoding a set of textures against a single shared codebook
+/// with [`Compressor::shared_codebook`] leaves them out of every file, and a
+/// [`Transcoder::with_shared_codebook`] decodes it once for all of them.
+///
+/// ```no_run
+/// # use std::sync::Arc;
+/// # let pixels = vec![0; 32 * 32 * 4];
+/// # let images = [basis::SourceImage::new(32, 32, &pixels)];
+/// let codebook = Arc::new(basis::SharedCodebook::build(&basis::Compressor::new(), &images).unwrap());
+/// std::fs::write("ui.codebook.basis", codebook.file()).unwrap();
+///
+/// let compressor = basis::Compressor::new().shared_codebook(Some(codebook.clone()));
+/// let file = compressor.compress(&images[..1]).unwrap();
+///
+/// let transcoder = basis::Transcoder::with_shared_codebook(codebook);
+/// let prepared = transcoder.prepare_transcoding(&file.data).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SharedCodebook {
+    inner: *mut sys::basisu_lowlevel_etc1s_transcoder,
+    file: Vec<u8>,
+}
+impl SharedCodebook {
+    /// Uses the codebooks of an ETC1S `.basis` file, like one written from [`file`](Self::file).
+    ///
+    /// Returns [`BasisError::UnsupportedBasisFormat`] for UASTC files, and [`BasisError::InvalidFile`] if the
+    /// file was itself encoded against a shared codebook.
+    pub fn from_basis_file(file: Vec<u8>) -> Result<Self, BasisError> {
+        init();
+
+        let transcoder = Transcoder::new();
+        let basis_format = transcoder.get_tex_format(&file)?;
+        if basis_format != BasisTextureFormat::Etc1s {
+            return Err(BasisError::UnsupportedBasisFormat(basis_format));
+        }
+
+        let length = validate_slice_length(&file)?;
+
+        let inner = unsafe { sys::basisrs_create_global_codebooks(file.as_ptr() as _, length) };
+
+        if inner.is_null() {
+            return Err(BasisError::InvalidFile);
+        }
+
+        Ok(Self { inner, file })
+    }
+
+    /// Generates a codebook suited to every image in `images`, using the ETC1S settings of `compressor`.
+    pub fn build(compressor: &Compressor, images: &[SourceImage<'_>]) -> Result<Self, BasisError> {
+        let file = compressor
+            .clone()
+            .basis_format(BasisTextureFormat::Etc1s)
+            .texture_type(TextureType::D2)
+            .container(Container::Basis)
+            .shared_codebook(None)
+            .compress(images)?;
+
+        Self::from_basis_file(file.data)
+    }
+
+    /// The `.basis` file holding the codebook. Files encoded against the codebook need it to be transcoded.
+    pub fn file(&self) -> &[u8] {
+        &self.file
+    }
+
+    pub(crate) fn as_internal(&self) -> *const sys::basisu_lowlevel_etc1s_transcoder {
+        self.inner
+    }
+}
+impl Drop for SharedCodebook {
+    fn drop(&mut self) {
+        unsafe { sys::basisrs_destroy_global_codebooks(self.inner) }
+    }
+}
+
+// The codebooks are only read once decoded.
+unsafe impl Send for SharedCodebook {}
+unsafe impl Sync for SharedCodebook {}
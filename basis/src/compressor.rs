@@ -0,0 +1,720 @@
+use crate::{
+    init, read_vector_u8, sys, BasisError, BasisTextureFormat, SharedCodebook, TextureType, Transcoder, UserData,
+};
+use once_cell::sync::Lazy;
+use std::{
+    any::Any,
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
+    ptr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+// Only set up once something is compressed, so transcoding alone doesn't pay for the encoder's tables.
+static ENCODER_STATE: Lazy<()> = Lazy::new(|| {
+    init();
+    unsafe { sys::basisrs_init_encoder() }
+});
+
+fn init_encoder() {
+    Lazy::force(&ENCODER_STATE);
+}
+
+/// A single source image of tightly packed RGBA8 pixels.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SourceImage<'a> {
+    pub width: u32,
+    pub height: u32,
+    /// `width * height * 4` bytes, row by row from the top.
+    pub data: &'a [u8],
+}
+impl<'a> SourceImage<'a> {
+    pub fn new(width: u32, height: u32, data: &'a [u8]) -> Self {
+        Self { width, height, data }
+    }
+
+    fn is_valid(&self) -> bool {
+        // basis_universal counts pixels in 32 bits.
+        let length = self
+            .width
+            .checked_mul(self.height)
+            .and_then(|pixels| (pixels as usize).checked_mul(4));
+
+        self.width != 0 && self.height != 0 && length == Some(self.data.len())
+    }
+}
+
+/// Trade-off between encoding speed and quality for UASTC.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UastcLevel {
+    Fastest,
+    Faster,
+    Default,
+    Slower,
+    VerySlow,
+}
+impl UastcLevel {
+    fn as_internal(self) -> u32 {
+        self as u32
+    }
+}
+
+/// UASTC rate-distortion optimization, which trades quality for output that compresses better with
+/// LZ-style compressors like Zstandard.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UastcRdo {
+    /// Higher values give smaller files of lower quality.
+    pub lambda: f32,
+    /// Bytes to look back for matches in, from 64 to 65536. Larger is smaller but slower.
+    pub dictionary_size: u32,
+}
+impl Default for UastcRdo {
+    fn default() -> Self {
+        Self {
+            lambda: 1.0,
+            dictionary_size: 4096,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Container {
+    /// A `.basis` file, readable by [`Transcoder`](crate::Transcoder).
+    Basis,
+    /// A `.ktx2` file, readable by [`Ktx2Transcoder`](crate::Ktx2Transcoder).
+    Ktx2,
+}
+
+/// Output of a [`Compressor`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressedFile {
+    pub container: Container,
+    pub data: Vec<u8>,
+    /// Size of the encoded texture in a `.basis` file, which is always produced.
+    pub basis_file_size: usize,
+    /// Size of the `.ktx2` file, after Zstandard supercompression if enabled.
+    pub ktx2_file_size: Option<usize>,
+    /// Present if [`Compressor::compute_stats`] was enabled.
+    pub stats: Option<CompressionStats>,
+}
+
+/// Quality and size of an encoded file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressionStats {
+    /// Bits per texel of the `.basis` file, over every image level.
+    pub bits_per_texel: f32,
+    /// Same as [`FileInfo::total_endpoints`](crate::FileInfo::total_endpoints). 0 for UASTC.
+    pub total_endpoints: u32,
+    pub endpoint_codebook_size: u32,
+    /// Same as [`FileInfo::total_selectors`](crate::FileInfo::total_selectors). 0 for UASTC.
+    pub total_selectors: u32,
+    pub selector_codebook_size: u32,
+    /// One entry per image level, ordered by image then level.
+    pub levels: Vec<LevelStats>,
+}
+
+/// Quality of a single encoded image level, measured against its source.
+///
+/// PSNRs are in decibels, and infinite where the level matches its source exactly.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LevelStats {
+    pub image_index: u32,
+    pub level_index: u32,
+    pub width: u32,
+    pub height: u32,
+    pub rgb_psnr: f32,
+    pub rgba_psnr: f32,
+    pub alpha_psnr: f32,
+    /// PSNR of the Rec. 709 luma.
+    pub luma_psnr: f32,
+    /// SSIM of the Rec. 709 luma, from 0 to 1.
+    pub luma_ssim: f32,
+}
+
+/// Filter kernel used to downsample mip levels.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MipFilter {
+    Box,
+    Tent,
+    Kaiser,
+    Lanczos4,
+    Mitchell,
+}
+impl MipFilter {
+    fn as_internal(self) -> u32 {
+        self as u32
+    }
+}
+
+/// How the encoder generates mip levels from each source image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MipmapOptions {
+    pub filter: MipFilter,
+    /// Filter in linear space, converting from and back to sRGB. Disable for non-color data.
+    pub srgb: bool,
+    /// Sample across the edges as if the image repeats, instead of clamping to them.
+    pub wrap: bool,
+    /// Renormalize the RGB channels of every texel as a unit vector, for normal maps.
+    pub renormalize: bool,
+    /// Levels stop once both dimensions are at most this many pixels. Must be at least 1.
+    pub smallest_dimension: u32,
+}
+impl Default for MipmapOptions {
+    fn default() -> Self {
+        Self {
+            filter: MipFilter::Kaiser,
+            srgb: true,
+            wrap: false,
+            renormalize: false,
+            smallest_dimension: 1,
+        }
+    }
+}
+
+/// Stages of a compression, in the order they run.
+///
+/// basis_universal doesn't measure its progress within a phase, so each phase is reported once as it
+/// starts, except [`LoadingImages`](Self::LoadingImages) which is reported per source image. How long each
+/// phase takes depends heavily on the settings: ETC1S codebook generation and UASTC RDO can take minutes on
+/// large textures.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CompressionPhase {
+    /// Source images are being copied into the encoder.
+    LoadingImages,
+    /// Mipmaps are being generated and the images split into blocks.
+    PreparingImages,
+    /// ETC1S only: endpoints and selectors are being clustered into codebooks.
+    GeneratingCodebooks,
+    /// The blocks of each slice are being encoded. UASTC RDO runs here too, slice by slice.
+    EncodingSlices,
+    /// ETC1S only: endpoint and selector RDO, followed by entropy coding.
+    Rdo,
+    /// The file is being assembled, then transcoded back to validate it and compute stats.
+    WritingFile,
+    Done,
+}
+impl CompressionPhase {
+    fn from_internal(value: sys::basisrs_compression_phase) -> Self {
+        match value {
+            sys::basisrs_compression_phase_cBASISRSPhasePreparingImages => Self::PreparingImages,
+            sys::basisrs_compression_phase_cBASISRSPhaseGeneratingCodebooks => Self::GeneratingCodebooks,
+            sys::basisrs_compression_phase_cBASISRSPhaseEncodingSlices => Self::EncodingSlices,
+            sys::basisrs_compression_phase_cBASISRSPhaseRdo => Self::Rdo,
+            sys::basisrs_compression_phase_cBASISRSPhaseWritingFile => Self::WritingFile,
+            _ => unreachable!("unknown compression phase {}", value),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CompressionProgress {
+    pub phase: CompressionPhase,
+    /// Source images copied into the encoder so far, which is every source image once loading is done.
+    pub images_loaded: u32,
+}
+
+/// Cancels a [`Compressor::compress_with_progress`] call from another thread.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every compression using this token or one of its clones.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// Encodes RGBA8 images into a `.basis` or `.ktx2` file.
+///
+/// ```no_run
+/// # let pixels = vec![0; 256 * 256 * 4];
+/// let file = basis::Compressor::new()
+///     .basis_format(basis::BasisTextureFormat::Etc1s)
+///     .quality_level(192)
+///     .compress(&[basis::SourceImage::new(256, 256, &pixels)])
+///     .unwrap();
+///
+/// let transcoder = basis::Transcoder::new();
+/// assert_eq!(transcoder.get_total_images(&file.data).unwrap().get(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Compressor {
+    basis_format: BasisTextureFormat,
+    texture_type: TextureType,
+    us_per_frame: u32,
+    quality_level: u32,
+    uastc_level: UastcLevel,
+    perceptual: bool,
+    srgb: bool,
+    y_flip: bool,
+    userdata: UserData,
+    uastc_rdo: Option<UastcRdo>,
+    container: Container,
+    zstd_level: Option<i32>,
+    compute_stats: bool,
+    mipmaps: Option<MipmapOptions>,
+    shared_codebook: Option<Arc<SharedCodebook>>,
+}
+impl Compressor {
+    pub fn new() -> Self {
+        Self {
+            basis_format: BasisTextureFormat::Etc1s,
+            texture_type: TextureType::D2,
+            us_per_frame: 0,
+            quality_level: 128,
+            uastc_level: UastcLevel::Default,
+            perceptual: true,
+            srgb: true,
+            y_flip: false,
+            userdata: UserData { word0: 0, word1: 0 },
+            uastc_rdo: None,
+            container: Container::Basis,
+            zstd_level: None,
+            compute_stats: false,
+            mipmaps: None,
+            shared_codebook: None,
+        }
+    }
+
+    pub fn basis_format(mut self, basis_format: BasisTextureFormat) -> Self {
+        self.basis_format = basis_format;
+        self
+    }
+
+    /// How the source images relate to each other.
+    ///
+    /// Every type but [`TextureType::D2`] requires all images to have the same dimensions.
+    /// [`TextureType::CubemapArray`] additionally requires square images, six per cube in
+    /// [`CubeFace`](crate::CubeFace) order.
+    pub fn texture_type(mut self, texture_type: TextureType) -> Self {
+        self.texture_type = texture_type;
+        self
+    }
+
+    /// Microseconds each frame is shown for. Only stored for [`TextureType::VideoFrames`].
+    pub fn us_per_frame(mut self, us_per_frame: u32) -> Self {
+        self.us_per_frame = us_per_frame;
+        self
+    }
+
+    /// ETC1S quality, from 1 to 255. Higher is larger and better looking. Ignored for UASTC.
+    pub fn quality_level(mut self, quality_level: u32) -> Self {
+        self.quality_level = quality_level;
+        self
+    }
+
+    /// UASTC encoding effort. Ignored for ETC1S.
+    pub fn uastc_level(mut self, uastc_level: UastcLevel) -> Self {
+        self.uastc_level = uastc_level;
+        self
+    }
+
+    /// Enables rate-distortion optimization of UASTC output. Ignored for ETC1S.
+    pub fn uastc_rdo(mut self, uastc_rdo: Option<UastcRdo>) -> Self {
+        self.uastc_rdo = uastc_rdo;
+        self
+    }
+
+    pub fn container(mut self, container: Container) -> Self {
+        self.container = container;
+        self
+    }
+
+    /// Zstandard level, from 1 to 22, to supercompress UASTC KTX2 files with. ETC1S KTX2 files are always
+    /// supercompressed with BasisLZ, and `.basis` files are never supercompressed.
+    pub fn zstd_level(mut self, zstd_level: Option<i32>) -> Self {
+        self.zstd_level = zstd_level;
+        self
+    }
+
+    /// Generates a full mip chain for every source image. `None`, the default, only encodes the source images.
+    pub fn mipmaps(mut self, mipmaps: Option<MipmapOptions>) -> Self {
+        self.mipmaps = mipmaps;
+        self
+    }
+
+    /// Encodes ETC1S files against a shared codebook instead of storing codebooks in every file.
+    ///
+    /// Such files can only be transcoded by a [`Transcoder`] using the same codebook.
+    pub fn shared_codebook(mut self, shared_codebook: Option<Arc<SharedCodebook>>) -> Self {
+        self.shared_codebook = shared_codebook;
+        self
+    }
+
+    /// Measure the quality of every image level once encoded, returned in [`CompressedFile::stats`].
+    ///
+    /// This transcodes the whole file after encoding, so it's off by default.
+    pub fn compute_stats(mut self, compute_stats: bool) -> Self {
+        self.compute_stats = compute_stats;
+        self
+    }
+
+    /// Weigh errors by how visible they are, instead of treating every channel the same.
+    /// Disable this for normal maps and other non-color data.
+    pub fn perceptual(mut self, perceptual: bool) -> Self {
+        self.perceptual = perceptual;
+        self
+    }
+
+    /// Whether the images are in sRGB color space, as opposed to linear. Stored in KTX2 files.
+    ///
+    /// Mip filtering is controlled separately by [`MipmapOptions::srgb`].
+    pub fn srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    /// Flip the images vertically before encoding.
+    pub fn y_flip(mut self, y_flip: bool) -> Self {
+        self.y_flip = y_flip;
+        self
+    }
+
+    /// Stored in the file, and returned by [`Transcoder::get_userdata`](crate::Transcoder::get_userdata).
+    pub fn userdata(mut self, userdata: UserData) -> Self {
+        self.userdata = userdata;
+        self
+    }
+
+    /// Encodes `images` into a file, one image per source image.
+    ///
+    /// Video frames are encoded in order, with the first one as an I-frame. Settings outside of their
+    /// documented range return [`BasisError::InvalidParameter`].
+    pub fn compress(&self, images: &[SourceImage<'_>]) -> Result<CompressedFile, BasisError> {
+        self.compress_with_progress(images, None, |_| {})
+    }
+
+    /// Same as [`compress`](Self::compress), but reports progress to `progress` and stops with
+    /// [`BasisError::Cancelled`] once `cancellation` is cancelled.
+    ///
+    /// `progress` is called on this thread at the start of every [`CompressionPhase`]. `cancellation` is
+    /// checked before each source image is loaded and at the start of each phase. A phase that has started
+    /// always runs to completion, so a cancellation during a long ETC1S or UASTC RDO encode only takes
+    /// effect once that phase finishes, but the phases after it are skipped.
+    pub fn compress_with_progress<F>(
+        &self,
+        images: &[SourceImage<'_>],
+        cancellation: Option<&CancellationToken>,
+        mut progress: F,
+    ) -> Result<CompressedFile, BasisError>
+    where
+        F: FnMut(CompressionProgress),
+    {
+        init_encoder();
+
+        self.validate_parameters()?;
+
+        if images.is_empty() {
+            return Err(BasisError::NoSourceImages);
+        }
+
+        if let Some(image_index) = images.iter().position(|image| !image.is_valid()) {
+            return Err(BasisError::InvalidSourceImage {
+                image_index: image_index as u32,
+            });
+        }
+
+        self.validate_texture_type(images)?;
+
+        if self.shared_codebook.is_some() && self.basis_format != BasisTextureFormat::Etc1s {
+            return Err(BasisError::UnsupportedBasisFormat(self.basis_format));
+        }
+
+        let check_cancelled = || match cancellation {
+            Some(cancellation) if cancellation.is_cancelled() => Err(BasisError::Cancelled),
+            _ => Ok(()),
+        };
+
+        let compressor = RawCompressor::new();
+
+        for (image_index, image) in images.iter().enumerate() {
+            check_cancelled()?;
+
+            progress(CompressionProgress {
+                phase: CompressionPhase::LoadingImages,
+                images_loaded: image_index as u32,
+            });
+
+            let source_image = sys::basisrs_source_image {
+                pixels: image.data.as_ptr(),
+                width: image.width,
+                height: image.height,
+            };
+
+            unsafe { sys::basisrs_compressor_add_source_image(compressor.inner, &source_image) };
+        }
+
+        let mipmaps = self.mipmaps.unwrap_or_default();
+
+        let params = sys::basisrs_compressor_params {
+            uastc: self.basis_format == BasisTextureFormat::UAstc,
+            quality_level: self.quality_level,
+            uastc_level: self.uastc_level.as_internal(),
+            perceptual: self.perceptual,
+            srgb: self.srgb,
+            y_flip: self.y_flip,
+            userdata0: self.userdata.word0,
+            userdata1: self.userdata.word1,
+            tex_type: self.texture_type.as_internal(),
+            us_per_frame: self.us_per_frame,
+            rdo_uastc: self.uastc_rdo.is_some(),
+            rdo_uastc_lambda: self.uastc_rdo.unwrap_or_default().lambda,
+            rdo_uastc_dict_size: self.uastc_rdo.unwrap_or_default().dictionary_size,
+            create_ktx2: self.container == Container::Ktx2,
+            ktx2_zstd: self.zstd_level.is_some(),
+            ktx2_zstd_level: self.zstd_level.unwrap_or(0),
+            compute_stats: self.compute_stats,
+            mip_gen: self.mipmaps.is_some(),
+            mip_filter: mipmaps.filter.as_internal(),
+            mip_srgb: mipmaps.srgb,
+            mip_wrapping: mipmaps.wrap,
+            mip_renormalize: mipmaps.renormalize,
+            mip_smallest_dimension: mipmaps.smallest_dimension as _,
+            global_codebooks: self
+                .shared_codebook
+                .as_ref()
+                .map_or(ptr::null(), |codebook| codebook.as_internal()),
+        };
+
+        let mut state = ProgressState {
+            images_loaded: images.len() as u32,
+            progress: &mut progress,
+            panic: None,
+        };
+
+        let res = unsafe {
+            sys::basisrs_compressor_process(
+                compressor.inner,
+                &params,
+                Some(report_phase),
+                &mut state as *mut ProgressState<'_> as *mut c_void,
+                cancellation.map_or(ptr::null(), |cancellation| {
+                    &*cancellation.cancelled as *const AtomicBool
+                }),
+            )
+        };
+
+        if let Some(payload) = state.panic {
+            panic::resume_unwind(payload);
+        }
+
+        match res {
+            0 => {}
+            -2 => return Err(BasisError::Cancelled),
+            _ => return Err(BasisError::CompressionFailed),
+        }
+
+        check_cancelled()?;
+
+        let basis_file = compressor.output_basis_file();
+        let ktx2_file = compressor.output_ktx2_file();
+
+        let file = CompressedFile {
+            container: self.container,
+            data: match self.container {
+                Container::Basis => basis_file.to_vec(),
+                Container::Ktx2 => ktx2_file.to_vec(),
+            },
+            basis_file_size: basis_file.len(),
+            ktx2_file_size: match self.container {
+                Container::Basis => None,
+                Container::Ktx2 => Some(ktx2_file.len()),
+            },
+            stats: if self.compute_stats {
+                Some(compressor.stats()?)
+            } else {
+                None
+            },
+        };
+
+        progress(CompressionProgress {
+            phase: CompressionPhase::Done,
+            images_loaded: images.len() as u32,
+        });
+
+        Ok(file)
+    }
+
+    fn validate_parameters(&self) -> Result<(), BasisError> {
+        if !(1..=255).contains(&self.quality_level) {
+            return Err(BasisError::InvalidParameter("quality_level"));
+        }
+
+        if let Some(rdo) = self.uastc_rdo {
+            if rdo.lambda.is_nan() || rdo.lambda <= 0.0 {
+                return Err(BasisError::InvalidParameter("uastc_rdo.lambda"));
+            }
+            if !(64..=65536).contains(&rdo.dictionary_size) {
+                return Err(BasisError::InvalidParameter("uastc_rdo.dictionary_size"));
+            }
+        }
+
+        match self.zstd_level {
+            Some(level) if !(1..=22).contains(&level) => return Err(BasisError::InvalidParameter("zstd_level")),
+            _ => {}
+        }
+
+        match self.mipmaps {
+            Some(options) if options.smallest_dimension == 0 => {
+                return Err(BasisError::InvalidParameter("mipmaps.smallest_dimension"))
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::manual_is_multiple_of)] // msrv doesn't allow this
+    fn validate_texture_type(&self, images: &[SourceImage<'_>]) -> Result<(), BasisError> {
+        match self.texture_type {
+            TextureType::D2 => return Ok(()),
+            TextureType::D2Array | TextureType::VideoFrames | TextureType::D3 => {}
+            TextureType::CubemapArray => {
+                if images.len() % 6 != 0 {
+                    return Err(BasisError::InvalidCubemapImageCount(images.len() as u32));
+                }
+
+                if images[0].width != images[0].height {
+                    return Err(BasisError::NonSquareCubemapFace { image_index: 0 });
+                }
+            }
+            TextureType::Total => return Err(BasisError::UnsupportedTextureType(self.texture_type)),
+        }
+
+        let first = &images[0];
+        if let Some(image_index) = images
+            .iter()
+            .position(|image| image.width != first.width || image.height != first.height)
+        {
+            return Err(BasisError::InconsistentImages {
+                image_index: image_index as u32,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Compressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The progress closure of a running compression, the image count it reports once loading is done, and a
+/// panic raised by the closure. Passed through basis_universal to [`report_phase`].
+struct ProgressState<'a> {
+    images_loaded: u32,
+    progress: &'a mut dyn FnMut(CompressionProgress),
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+unsafe extern "C" fn report_phase(user_data: *mut c_void, phase: sys::basisrs_compression_phase) -> bool {
+    let state = &mut *(user_data as *mut ProgressState<'_>);
+    let images_loaded = state.images_loaded;
+    let progress = &mut state.progress;
+
+    // Unwinding into basis_universal is undefined behaviour, so a panic stops the compression and is
+    // resumed once it has returned.
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        progress(CompressionProgress {
+            phase: CompressionPhase::from_internal(phase),
+            images_loaded,
+        })
+    }));
+
+    match res {
+        Ok(()) => true,
+        Err(payload) => {
+            state.panic = Some(payload);
+            false
+        }
+    }
+}
+
+/// Owns the encoder and its thread pool for the duration of a single compression.
+struct RawCompressor {
+    inner: *mut sys::basisrs_compressor,
+}
+impl RawCompressor {
+    fn new() -> Self {
+        Self {
+            inner: unsafe { sys::basisrs_create_compressor() },
+        }
+    }
+
+    fn output_basis_file(&self) -> &[u8] {
+        unsafe { read_vector_u8(sys::basisrs_compressor_get_output_basis_file(self.inner)) }
+    }
+
+    fn output_ktx2_file(&self) -> &[u8] {
+        unsafe { read_vector_u8(sys::basisrs_compressor_get_output_ktx2_file(self.inner)) }
+    }
+
+    /// Matches the encoder's per level stats with their image and level, and the file's codebook sizes.
+    fn stats(&self) -> Result<CompressionStats, BasisError> {
+        let file_info = Transcoder::new().get_file_info(self.output_basis_file())?;
+
+        let image_levels = file_info
+            .image_mipmap_levels
+            .iter()
+            .enumerate()
+            .flat_map(|(image_index, &total_levels)| (0..total_levels).map(move |level| (image_index as u32, level)));
+
+        let total_stats = unsafe { sys::basisrs_compressor_get_total_stats(self.inner) };
+
+        let mut levels = Vec::with_capacity(total_stats as _);
+        for (index, (image_index, level_index)) in (0..total_stats).zip(image_levels) {
+            let mut stats = sys::basisrs_image_stats {
+                width: 0,
+                height: 0,
+                rgb_psnr: 0.0,
+                rgba_psnr: 0.0,
+                alpha_psnr: 0.0,
+                luma_709_psnr: 0.0,
+                luma_709_ssim: 0.0,
+            };
+
+            let res = unsafe { sys::basisrs_compressor_get_stats(self.inner, index, &mut stats) };
+            assert!(res, "stats index {} out of range", index);
+
+            levels.push(LevelStats {
+                image_index,
+                level_index,
+                width: stats.width,
+                height: stats.height,
+                rgb_psnr: stats.rgb_psnr,
+                rgba_psnr: stats.rgba_psnr,
+                alpha_psnr: stats.alpha_psnr,
+                luma_psnr: stats.luma_709_psnr,
+                luma_ssim: stats.luma_709_ssim,
+            });
+        }
+
+        Ok(CompressionStats {
+            bits_per_texel: unsafe { sys::basisrs_compressor_get_bits_per_texel(self.inner) },
+            total_endpoints: file_info.total_endpoints,
+            endpoint_codebook_size: file_info.endpoint_codebook_size,
+            total_selectors: file_info.total_selectors,
+            selector_codebook_size: file_info.selector_codebook_size,
+            levels,
+        })
+    }
+}
+impl Drop for RawCompressor {
+    fn drop(&mut self) {
+        unsafe { sys::basisrs_destroy_compressor(self.inner) };
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{BasisError, DecodeFlags, PreparedBasisFile, TargetTextureFormat};
+use ddsfile::{AlphaMode, Caps2, D3D10ResourceDimension, Dds, DxgiFormat};
+use enum_primitive::FromPrimitive;
+
+impl TargetTextureFormat {
+    /// [`dxgi_format`](Self::dxgi_format) as the enum DDS files are written with, or `None` if D3D has no
+    /// equivalent.
+    pub fn dds_format(self, srgb: bool) -> Option<DxgiFormat> {
+        self.dxgi_format(srgb).and_then(DxgiFormat::from_u32)
+    }
+}
+
+impl<'a> PreparedBasisFile<'a> {
+    /// Transcodes every level of every image to `format` and stores them in a DDS file with a DX10 header.
+    ///
+    /// Cubemap files become cube DDS files, files with several images become texture arrays, and volumes
+    /// become 3D textures. Videos are written as arrays of their frames. `srgb` picks the sRGB variant of the
+    /// DXGI format where there is one.
+    ///
+    /// Mipmapped volumes return [`BasisError::UnsupportedTextureType`], as their levels keep every slice
+    /// while the mips of a DDS volume halve in depth.
+    ///
+    /// ```no_run
+    /// # let file = std::fs::read("texture.basis").unwrap();
+    /// let transcoder = basis::Transcoder::new();
+    /// let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    /// let dds = prepared
+    ///     .to_dds(basis::TargetTextureFormat::Bc7Rgba, true, basis::DecodeFlags::empty())
+    ///     .unwrap();
+    /// dds.write(&mut std::fs::File::create("texture.dds").unwrap()).unwrap();
+    /// ```
+    pub fn to_dds(
+        &mut self,
+        format: TargetTextureFormat,
+        srgb: bool,
+        decode_flags: DecodeFlags,
+    ) -> Result<Dds, BasisError> {
+        let dxgi_format = format
+            .dds_format(srgb)
+            .ok_or(BasisError::UnsupportedOutputFormat(format))?;
+
+        let texture = self.export_texture(format, decode_flags)?;
+        let is_cubemap = texture.faces == 6;
+
+        let (depth, caps2, resource_dimension) = if texture.depth > 0 {
+            (
+                Some(texture.depth),
+                Some(Caps2::VOLUME),
+                D3D10ResourceDimension::Texture3D,
+            )
+        } else if is_cubemap {
+            let caps2 = Caps2::CUBEMAP
+                | Caps2::CUBEMAP_POSITIVEX
+                | Caps2::CUBEMAP_NEGATIVEX
+                | Caps2::CUBEMAP_POSITIVEY
+                | Caps2::CUBEMAP_NEGATIVEY
+                | Caps2::CUBEMAP_POSITIVEZ
+                | Caps2::CUBEMAP_NEGATIVEZ;
+            (None, Some(caps2), D3D10ResourceDimension::Texture2D)
+        } else {
+            (None, None, D3D10ResourceDimension::Texture2D)
+        };
+
+        let mut dds = Dds::new_dxgi(
+            texture.height,
+            texture.width,
+            depth,
+            dxgi_format,
+            Some(texture.levels.len() as u32),
+            // Cube arrays count cubes rather than faces.
+            Some(texture.layers.max(1)),
+            caps2,
+            is_cubemap,
+            resource_dimension,
+            AlphaMode::Unknown,
+        )
+        .map_err(|_| BasisError::UnsupportedOutputFormat(format))?;
+
+        // DDS stores the whole mip chain of each layer and face one after another, and cubemap faces in the
+        // same order as basis files. Volumes only have one level, holding every slice.
+        let images_per_level = texture.levels[0].images.len();
+        let mut data = Vec::new();
+        for image_index in 0..images_per_level {
+            for level in &texture.levels {
+                data.extend_from_slice(&level.images[image_index]);
+            }
+        }
+        dds.data = data;
+
+        Ok(dds)
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{BasisTextureFormat, TargetTextureFormat, TextureType};
+use std::fmt;
+
+/// Error returned by every fallible operation in this crate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BasisError {
+    /// The file doesn't start with a valid .basis header.
+    InvalidHeader,
+    /// The file is 4 GiB or larger, which basis_universal can't address.
+    FileTooLarge,
+    /// The file's header is valid, but its checksums don't match its contents.
+    ChecksumMismatch,
+    /// The file's header is valid, but the rest of the file couldn't be parsed.
+    InvalidFile,
+    /// The file contains a texture type this crate doesn't know about.
+    UnknownTextureType(u32),
+    /// The file contains a basis texture format this crate doesn't know about.
+    UnknownBasisFormat(u32),
+    ImageOutOfRange {
+        image_index: u32,
+        total_images: u32,
+    },
+    LevelOutOfRange {
+        image_index: u32,
+        level_index: u32,
+        total_levels: u32,
+    },
+    LayerOutOfRange {
+        layer_index: u32,
+        total_layers: u32,
+    },
+    FaceOutOfRange {
+        face_index: u32,
+        total_faces: u32,
+    },
+    SliceOutOfRange {
+        slice_index: u32,
+        total_slices: u32,
+    },
+    FrameOutOfRange {
+        frame: u32,
+        frame_count: u32,
+    },
+    /// The transcoder is already used by another [`PreparedBasisFile`](crate::PreparedBasisFile).
+    TranscoderBusy,
+    /// The basis texture format can't be transcoded to the target format.
+    UnsupportedTarget {
+        basis_format: BasisTextureFormat,
+        target: TargetTextureFormat,
+    },
+    BufferTooSmall {
+        required: usize,
+        provided: usize,
+    },
+    /// The output's size doesn't fit in memory, or in the 32 bit sizes the transcoder works with.
+    OutputTooLarge,
+    /// A source channel index isn't between 0 and 3.
+    InvalidChannel(u8),
+    NotVideo(TextureType),
+    /// The output file format or graphics API has no equivalent of the target format.
+    UnsupportedOutputFormat(TargetTextureFormat),
+    /// The operation doesn't work on files of this basis texture format.
+    UnsupportedBasisFormat(BasisTextureFormat),
+    /// The operation doesn't work on files of this texture type.
+    UnsupportedTextureType(TextureType),
+    /// A cubemap file's image count isn't a multiple of six.
+    InvalidCubemapImageCount(u32),
+    /// A cubemap face isn't square.
+    NonSquareCubemapFace {
+        image_index: u32,
+    },
+    /// The image's resolution or level count differs from the other images, which its texture type forbids.
+    InconsistentImages {
+        image_index: u32,
+    },
+    /// A P-frame was requested without decoding the frame before it first.
+    OutOfOrderFrame {
+        requested: u32,
+        expected: u32,
+    },
+    /// basis_universal failed to transcode. In debug builds, details are printed to stderr.
+    TranscodeFailed,
+    /// The compressor was given no images to encode.
+    NoSourceImages,
+    /// The source image has a zero dimension, has more pixels than fit in a `u32`, or its data isn't
+    /// `width * height * 4` bytes long.
+    InvalidSourceImage {
+        image_index: u32,
+    },
+    /// A compressor or layout setting is outside of its documented range. Holds the setting's name.
+    InvalidParameter(&'static str),
+    /// basis_universal failed to encode. In debug builds, details are printed to stderr.
+    CompressionFailed,
+    /// The compression was cancelled through its [`CancellationToken`](crate::CancellationToken).
+    Cancelled,
+}
+
+impl fmt::Display for BasisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            BasisError::InvalidHeader => write!(f, "File does not have a valid basis header"),
+            BasisError::FileTooLarge => write!(f, "File is too large to address"),
+            BasisError::ChecksumMismatch => write!(f, "File checksums do not match its contents"),
+            BasisError::InvalidFile => write!(f, "File could not be parsed"),
+            BasisError::UnknownTextureType(value) => write!(f, "Unknown texture type {}", value),
+            BasisError::UnknownBasisFormat(value) => write!(f, "Unknown basis texture format {}", value),
+            BasisError::ImageOutOfRange {
+                image_index,
+                total_images,
+            } => write!(
+                f,
+                "Image {} is out of range, file has {} images",
+                image_index, total_images
+            ),
+            BasisError::LevelOutOfRange {
+                image_index,
+                level_index,
+                total_levels,
+            } => write!(
+                f,
+                "Level {} is out of range, image {} has {} levels",
+                level_index, image_index, total_levels
+            ),
+            BasisError::LayerOutOfRange {
+                layer_index,
+                total_layers,
+            } => write!(
+                f,
+                "Layer {} is out of range, file has {} layers",
+                layer_index, total_layers
+            ),
+            BasisError::FaceOutOfRange {
+                face_index,
+                total_faces,
+            } => write!(f, "Face {} is out of range, file has {} faces", face_index, total_faces),
+            BasisError::SliceOutOfRange {
+                slice_index,
+                total_slices,
+            } => write!(
+                f,
+                "Slice {} is out of range, file has {} slices",
+                slice_index, total_slices
+            ),
+            BasisError::FrameOutOfRange { frame, frame_count } => {
+                write!(f, "Frame {} is out of range, video has {} frames", frame, frame_count)
+            }
+            BasisError::TranscoderBusy => write!(f, "Transcoder is already transcoding another file"),
+            BasisError::UnsupportedTarget {
+                basis_format,
+                target: TargetTextureFormat::Rgba4444,
+            } => write!(
+                f,
+                "Format {:?} cannot be converted from {:?} because of a bug",
+                TargetTextureFormat::Rgba4444,
+                basis_format
+            ),
+            BasisError::UnsupportedTarget { basis_format, target } => {
+                write!(f, "Format {:?} cannot be converted from {:?}", target, basis_format)
+            }
+            BasisError::BufferTooSmall { required, provided } => write!(
+                f,
+                "Output buffer is {} bytes, but {} bytes are required",
+                provided, required
+            ),
+            BasisError::OutputTooLarge => write!(f, "Output is too large to address"),
+            BasisError::InvalidChannel(channel) => write!(f, "Channel {} is not between 0 and 3", channel),
+            BasisError::NotVideo(tex_type) => write!(f, "Texture type {:?} is not a video", tex_type),
+            BasisError::UnsupportedOutputFormat(target) => {
+                write!(f, "Target format {:?} has no equivalent in this output", target)
+            }
+            BasisError::UnsupportedBasisFormat(basis_format) => {
+                write!(f, "Operation is not supported on basis format {:?}", basis_format)
+            }
+            BasisError::UnsupportedTextureType(tex_type) => {
+                write!(f, "Operation is not supported on texture type {:?}", tex_type)
+            }
+            BasisError::InvalidCubemapImageCount(total_images) => {
+                write!(f, "Cubemap has {} images, which is not a multiple of 6", total_images)
+            }
+            BasisError::NonSquareCubemapFace { image_index } => {
+                write!(f, "Cubemap face {} is not square", image_index)
+            }
+            BasisError::InconsistentImages { image_index } => write!(
+                f,
+                "Image {} does not have the same resolution and level count as the other images",
+                image_index
+            ),
+            BasisError::OutOfOrderFrame { requested, expected } => write!(
+                f,
+                "Frame {} is a P-frame and cannot be decoded before frame {}",
+                requested, expected
+            ),
+            BasisError::TranscodeFailed => write!(f, "Transcoding failed. If in debug mode, check stderr"),
+            BasisError::NoSourceImages => write!(f, "No images were given to compress"),
+            BasisError::InvalidSourceImage { image_index } => write!(
+                f,
+                "Source image {} is empty or its data does not match its dimensions",
+                image_index
+            ),
+            BasisError::InvalidParameter(name) => write!(f, "Parameter {} is out of range", name),
+            BasisError::CompressionFailed => write!(f, "Compression failed. If in debug mode, check stderr"),
+            BasisError::Cancelled => write!(f, "Compression was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for BasisError {}
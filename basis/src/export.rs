@@ -0,0 +1,574 @@
+use crate::{BasisError, DecodeFlags, PreparedBasisFile, TargetTextureFormat, TextureType};
+
+const KTX1_IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x31, 0x31, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+const KTX2_IDENTIFIER: [u8; 12] = [0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A];
+/// `PVR\x03` read as a little endian integer.
+const PVR3_VERSION: u32 = 0x0352_5650;
+const ASTC_MAGIC: u32 = 0x5CA1_AB13;
+
+/// Every image of a file transcoded for a container that stores whole levels one after another.
+pub(crate) struct ExportedTexture {
+    pub width: u32,
+    pub height: u32,
+    /// Depth of a volume, or 0 for other textures.
+    pub depth: u32,
+    /// Layers of an array, or 0 for textures that aren't arrays.
+    pub layers: u32,
+    /// 6 for cubemaps, 1 otherwise.
+    pub faces: u32,
+    pub levels: Vec<ExportedLevel>,
+}
+
+pub(crate) struct ExportedLevel {
+    pub width: u32,
+    pub height: u32,
+    /// Every layer, then every face, then every depth slice, tightly packed.
+    pub images: Vec<Vec<u8>>,
+}
+
+/// OpenGL enums a KTX1 file describes its format with, besides the internal format.
+struct GlFormat {
+    base_internal_format: u32,
+    /// 0 for compressed formats.
+    format: u32,
+    /// 0 for compressed formats.
+    ty: u32,
+}
+
+/// How a KTX2 data format descriptor describes a format.
+struct DfdFormat {
+    color_model: u8,
+    /// Channel ID and bit count of every sample, from the least significant bit of a texel or block.
+    samples: &'static [(u8, u8)],
+}
+
+// Khronos data format descriptor values used by KTX2.
+const KHR_DF_MODEL_RGBSDA: u8 = 1;
+const KHR_DF_MODEL_BC1A: u8 = 128;
+const KHR_DF_MODEL_BC3: u8 = 130;
+const KHR_DF_MODEL_BC4: u8 = 131;
+const KHR_DF_MODEL_BC5: u8 = 132;
+const KHR_DF_MODEL_BC7: u8 = 134;
+const KHR_DF_MODEL_ETC2: u8 = 161;
+const KHR_DF_MODEL_ASTC: u8 = 162;
+const KHR_DF_MODEL_PVRTC: u8 = 164;
+const KHR_DF_MODEL_PVRTC2: u8 = 165;
+const KHR_DF_CHANNEL_RED: u8 = 0;
+const KHR_DF_CHANNEL_GREEN: u8 = 1;
+const KHR_DF_CHANNEL_BLUE: u8 = 2;
+const KHR_DF_CHANNEL_ALPHA: u8 = 15;
+const KHR_DF_CHANNEL_ETC2_COLOR: u8 = 2;
+const KHR_DF_SAMPLE_DATATYPE_LINEAR: u8 = 0x80;
+const KHR_DF_PRIMARIES_BT709: u8 = 1;
+const KHR_DF_TRANSFER_LINEAR: u8 = 1;
+const KHR_DF_TRANSFER_SRGB: u8 = 2;
+
+impl TargetTextureFormat {
+    /// GL enums a KTX1 file gives besides the internal format.
+    fn ktx1_gl_format(self) -> GlFormat {
+        const GL_RED: u32 = 0x1903;
+        const GL_RGB: u32 = 0x1907;
+        const GL_RGBA: u32 = 0x1908;
+        const GL_RG: u32 = 0x8227;
+        const GL_UNSIGNED_BYTE: u32 = 0x1401;
+        const GL_UNSIGNED_SHORT_4_4_4_4: u32 = 0x8033;
+        const GL_UNSIGNED_SHORT_5_6_5: u32 = 0x8363;
+        const GL_UNSIGNED_SHORT_5_6_5_REV: u32 = 0x8364;
+
+        let compressed = |base_internal_format: u32| GlFormat {
+            base_internal_format,
+            format: 0,
+            ty: 0,
+        };
+
+        match self {
+            Self::Bc4R | Self::EacR11 => compressed(GL_RED),
+            Self::Bc5Rg | Self::EacRg11 => compressed(GL_RG),
+            Self::Etc1Rgb | Self::Bc1Rgb | Self::Pvrtc1Rgb | Self::AtcRgb | Self::Fxt1Rgb => compressed(GL_RGB),
+            Self::Etc2Rgba
+            | Self::Bc3Rgba
+            | Self::Bc7Rgba
+            | Self::Pvrtc1Rgba
+            | Self::AstcRgba
+            | Self::AtcRgbA
+            | Self::Pvrtc2Rgb
+            | Self::Pvrtc2Rgba => compressed(GL_RGBA),
+            Self::Rgba32 => GlFormat {
+                base_internal_format: GL_RGBA,
+                format: GL_RGBA,
+                ty: GL_UNSIGNED_BYTE,
+            },
+            Self::Rgb565 | Self::Bgr565 => GlFormat {
+                base_internal_format: GL_RGB,
+                format: GL_RGB,
+                // Red is in the most significant bits of RGB565, and the least significant bits of BGR565.
+                ty: if self == Self::Rgb565 {
+                    GL_UNSIGNED_SHORT_5_6_5
+                } else {
+                    GL_UNSIGNED_SHORT_5_6_5_REV
+                },
+            },
+            Self::Rgba4444 => GlFormat {
+                base_internal_format: GL_RGBA,
+                format: GL_RGBA,
+                ty: GL_UNSIGNED_SHORT_4_4_4_4,
+            },
+        }
+    }
+
+    fn dfd_format(self) -> Option<DfdFormat> {
+        let (color_model, samples): (u8, &'static [(u8, u8)]) = match self {
+            Self::Etc1Rgb => (KHR_DF_MODEL_ETC2, &[(KHR_DF_CHANNEL_ETC2_COLOR, 64)]),
+            Self::Etc2Rgba => (
+                KHR_DF_MODEL_ETC2,
+                &[(KHR_DF_CHANNEL_ALPHA, 64), (KHR_DF_CHANNEL_ETC2_COLOR, 64)],
+            ),
+            Self::Bc1Rgb => (KHR_DF_MODEL_BC1A, &[(0, 64)]),
+            Self::Bc3Rgba => (KHR_DF_MODEL_BC3, &[(KHR_DF_CHANNEL_ALPHA, 64), (0, 64)]),
+            Self::Bc4R => (KHR_DF_MODEL_BC4, &[(0, 64)]),
+            Self::Bc5Rg => (KHR_DF_MODEL_BC5, &[(0, 64), (1, 64)]),
+            Self::Bc7Rgba => (KHR_DF_MODEL_BC7, &[(0, 128)]),
+            Self::Pvrtc1Rgb | Self::Pvrtc1Rgba => (KHR_DF_MODEL_PVRTC, &[(0, 64)]),
+            Self::Pvrtc2Rgb | Self::Pvrtc2Rgba => (KHR_DF_MODEL_PVRTC2, &[(0, 64)]),
+            Self::AstcRgba => (KHR_DF_MODEL_ASTC, &[(0, 128)]),
+            Self::EacR11 => (KHR_DF_MODEL_ETC2, &[(0, 64)]),
+            Self::EacRg11 => (KHR_DF_MODEL_ETC2, &[(0, 64), (1, 64)]),
+            Self::Rgba32 => (
+                KHR_DF_MODEL_RGBSDA,
+                &[
+                    (KHR_DF_CHANNEL_RED, 8),
+                    (KHR_DF_CHANNEL_GREEN, 8),
+                    (KHR_DF_CHANNEL_BLUE, 8),
+                    (KHR_DF_CHANNEL_ALPHA, 8),
+                ],
+            ),
+            Self::Rgb565 => (
+                KHR_DF_MODEL_RGBSDA,
+                &[
+                    (KHR_DF_CHANNEL_BLUE, 5),
+                    (KHR_DF_CHANNEL_GREEN, 6),
+                    (KHR_DF_CHANNEL_RED, 5),
+                ],
+            ),
+            Self::Bgr565 => (
+                KHR_DF_MODEL_RGBSDA,
+                &[
+                    (KHR_DF_CHANNEL_RED, 5),
+                    (KHR_DF_CHANNEL_GREEN, 6),
+                    (KHR_DF_CHANNEL_BLUE, 5),
+                ],
+            ),
+            Self::Rgba4444 => (
+                KHR_DF_MODEL_RGBSDA,
+                &[
+                    (KHR_DF_CHANNEL_ALPHA, 4),
+                    (KHR_DF_CHANNEL_BLUE, 4),
+                    (KHR_DF_CHANNEL_GREEN, 4),
+                    (KHR_DF_CHANNEL_RED, 4),
+                ],
+            ),
+            Self::AtcRgb | Self::AtcRgbA | Self::Fxt1Rgb => return None,
+        };
+
+        Some(DfdFormat { color_model, samples })
+    }
+
+    /// PVR v3 pixel format of the format, or `None` if PVR files can't hold it.
+    fn pvr_format(self) -> Option<u64> {
+        Some(match self {
+            Self::Pvrtc1Rgb => 2,
+            Self::Pvrtc1Rgba => 3,
+            Self::Pvrtc2Rgb | Self::Pvrtc2Rgba => 5,
+            Self::Etc1Rgb => 6,
+            Self::Bc1Rgb => 7,
+            Self::Bc3Rgba => 11,
+            Self::Bc4R => 12,
+            Self::Bc5Rg => 13,
+            Self::Bc7Rgba => 15,
+            Self::Etc2Rgba => 23,
+            Self::EacR11 => 25,
+            Self::EacRg11 => 26,
+            Self::AstcRgba => 27,
+            // Uncompressed formats list their channel names in the low bytes and their bit counts in the
+            // high bytes.
+            Self::Rgba32 => u64::from_le_bytes(*b"rgba\x08\x08\x08\x08"),
+            _ => return None,
+        })
+    }
+
+    /// Size of the data type a big endian reader would swap the bytes of.
+    fn type_size(self) -> u32 {
+        match self {
+            Self::Rgb565 | Self::Bgr565 | Self::Rgba4444 => 2,
+            _ => 1,
+        }
+    }
+
+    /// Bytes per block, or bytes per pixel for uncompressed formats.
+    fn bytes_per_texel_block(self) -> usize {
+        if self.is_uncompressed() {
+            self.block_size() / 16
+        } else {
+            self.block_size()
+        }
+    }
+}
+
+impl<'a> PreparedBasisFile<'a> {
+    /// Transcodes every level of every image to `format` and stores them in a KTX1 file.
+    ///
+    /// Cubemap files become cubemaps, [`TextureType::D2Array`] and video files become arrays, and
+    /// [`TextureType::D3`] files become volumes. `srgb` picks the sRGB variant of the format where there is
+    /// one.
+    ///
+    /// Basis volumes keep every depth slice at every level, while the mip levels of these formats halve the
+    /// depth too, so volumes with mipmaps return [`BasisError::UnsupportedTextureType`] rather than dropping
+    /// slices.
+    pub fn to_ktx(
+        &mut self,
+        format: TargetTextureFormat,
+        srgb: bool,
+        decode_flags: DecodeFlags,
+    ) -> Result<Vec<u8>, BasisError> {
+        let internal_format = format
+            .gl_internal_format(srgb)
+            .ok_or(BasisError::UnsupportedOutputFormat(format))?;
+        let gl_format = format.ktx1_gl_format();
+        let texture = self.export_texture(format, decode_flags)?;
+
+        let mut out = KTX1_IDENTIFIER.to_vec();
+        for &value in &[
+            0x0403_0201,
+            gl_format.ty,
+            format.type_size(),
+            gl_format.format,
+            internal_format,
+            gl_format.base_internal_format,
+            texture.width,
+            texture.height,
+            texture.depth,
+            texture.layers,
+            texture.faces,
+            texture.levels.len() as u32,
+            0,
+        ] {
+            put_u32(&mut out, value);
+        }
+
+        for level in &texture.levels {
+            // Uncompressed rows are padded to GL's default unpack alignment of 4 bytes.
+            let row_bytes = level.width as usize * format.bytes_per_texel_block();
+            let padded_row_bytes = round_up(row_bytes, 4);
+            let image_size = if format.is_uncompressed() {
+                padded_row_bytes * level.height as usize
+            } else {
+                level.images[0].len()
+            };
+
+            // A cubemap that isn't an array gives the size of a single face.
+            let images_in_size = if texture.faces == 6 && texture.layers == 0 {
+                1
+            } else {
+                level.images.len()
+            };
+            put_u32(&mut out, (image_size * images_in_size) as u32);
+
+            for image in &level.images {
+                if format.is_uncompressed() {
+                    for row in image.chunks(row_bytes) {
+                        out.extend_from_slice(row);
+                        pad(&mut out, 4);
+                    }
+                } else {
+                    out.extend_from_slice(image);
+                }
+                pad(&mut out, 4);
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Transcodes every level of every image to `format` and stores them in a KTX2 file with a data format
+    /// descriptor and no supercompression.
+    ///
+    /// Images are laid out the same way as by [`to_ktx`](Self::to_ktx). ATC and FXT1 have no VkFormat, so
+    /// they can't be written.
+    pub fn to_ktx2(
+        &mut self,
+        format: TargetTextureFormat,
+        srgb: bool,
+        decode_flags: DecodeFlags,
+    ) -> Result<Vec<u8>, BasisError> {
+        let vk_format = format
+            .vk_format(srgb)
+            .ok_or(BasisError::UnsupportedOutputFormat(format))?;
+        // Formats without an sRGB variant are always linear.
+        let srgb = srgb && format.vk_format(false) != Some(vk_format);
+        let dfd_format = format.dfd_format().ok_or(BasisError::UnsupportedOutputFormat(format))?;
+        let texture = self.export_texture(format, decode_flags)?;
+
+        let dfd = data_format_descriptor(format, &dfd_format, srgb);
+
+        let level_count = texture.levels.len();
+        let dfd_offset = KTX2_IDENTIFIER.len() + 9 * 4 + 4 * 4 + 2 * 8 + level_count * 3 * 8;
+
+        // Levels are stored from the smallest to the largest, each aligned to its block size.
+        let alignment = format.bytes_per_texel_block().max(4);
+        let mut level_index = vec![(0, 0); level_count];
+        let mut offset = dfd_offset + dfd.len();
+        for (level_index_entry, level) in level_index.iter_mut().zip(&texture.levels).rev() {
+            offset = round_up(offset, alignment);
+            let length = level.images.iter().map(Vec::len).sum::<usize>();
+            *level_index_entry = (offset, length);
+            offset += length;
+        }
+
+        let mut out = KTX2_IDENTIFIER.to_vec();
+        for &value in &[
+            vk_format,
+            format.type_size(),
+            texture.width,
+            texture.height,
+            texture.depth,
+            texture.layers,
+            texture.faces,
+            level_count as u32,
+            0,
+            dfd_offset as u32,
+            dfd.len() as u32,
+            0,
+            0,
+        ] {
+            put_u32(&mut out, value);
+        }
+        put_u64(&mut out, 0);
+        put_u64(&mut out, 0);
+        for &(offset, length) in &level_index {
+            put_u64(&mut out, offset as u64);
+            put_u64(&mut out, length as u64);
+            put_u64(&mut out, length as u64);
+        }
+
+        out.extend_from_slice(&dfd);
+        for (level, &(offset, _)) in texture.levels.iter().zip(&level_index).rev() {
+            out.resize(offset, 0);
+            for image in &level.images {
+                out.extend_from_slice(image);
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Transcodes every level of every image to `format` and stores them in a PVR v3 file, as read by
+    /// PowerVR tools.
+    ///
+    /// Images are laid out the same way as by [`to_ktx`](Self::to_ktx). Besides the PVRTC formats, PVR files
+    /// can hold the BC, ETC, EAC, ASTC and RGBA32 targets.
+    pub fn to_pvr(
+        &mut self,
+        format: TargetTextureFormat,
+        srgb: bool,
+        decode_flags: DecodeFlags,
+    ) -> Result<Vec<u8>, BasisError> {
+        let pixel_format = format.pvr_format().ok_or(BasisError::UnsupportedOutputFormat(format))?;
+        // Formats without an sRGB variant are always linear.
+        let srgb = srgb && format.vk_format(false) != format.vk_format(true);
+        let texture = self.export_texture(format, decode_flags)?;
+
+        let mut out = Vec::new();
+        put_u32(&mut out, PVR3_VERSION);
+        // No flags, so alpha isn't premultiplied.
+        put_u32(&mut out, 0);
+        put_u64(&mut out, pixel_format);
+        for &value in &[
+            srgb as u32,
+            // Unsigned normalized bytes.
+            0,
+            texture.height,
+            texture.width,
+            texture.depth.max(1),
+            texture.layers.max(1),
+            texture.faces,
+            texture.levels.len() as u32,
+            // No metadata.
+            0,
+        ] {
+            put_u32(&mut out, value);
+        }
+
+        for level in &texture.levels {
+            for image in &level.images {
+                out.extend_from_slice(image);
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Transcodes a single image level to [`TargetTextureFormat::AstcRgba`] and stores it in an `.astc`
+    /// file, as read by the ARM ASTC encoder and other ASTC tools.
+    pub fn to_astc(
+        &mut self,
+        image_index: u32,
+        level_index: u32,
+        decode_flags: DecodeFlags,
+    ) -> Result<Vec<u8>, BasisError> {
+        let level_info = self.level_info(image_index, level_index)?;
+        let blocks =
+            self.transcode_image_level(image_index, level_index, TargetTextureFormat::AstcRgba, decode_flags)?;
+
+        let mut out = Vec::with_capacity(16 + blocks.len());
+        put_u32(&mut out, ASTC_MAGIC);
+        // Block width, height and depth.
+        out.extend_from_slice(&[4, 4, 1]);
+        // Image width, height and depth, as 24 bit integers.
+        for &size in &[level_info.orig_width, level_info.orig_height, 1] {
+            out.extend_from_slice(&size.to_le_bytes()[..3]);
+        }
+        out.extend_from_slice(&blocks);
+
+        Ok(out)
+    }
+
+    /// Transcodes every level of every image, grouped by level, after making sure the images have the same
+    /// resolution and level count.
+    pub(crate) fn export_texture(
+        &mut self,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+    ) -> Result<ExportedTexture, BasisError> {
+        let total_images = self.file_info.total_images;
+        let total_levels = self.file_info.image_mipmap_levels[0];
+        let level_info = self.level_info(0, 0)?;
+
+        for image_index in 1..total_images {
+            let image_level_info = self.level_info(image_index, 0)?;
+
+            if self.file_info.image_mipmap_levels[image_index as usize] != total_levels
+                || image_level_info.orig_width != level_info.orig_width
+                || image_level_info.orig_height != level_info.orig_height
+            {
+                return Err(BasisError::InconsistentImages { image_index });
+            }
+        }
+
+        let (depth, layers, faces) = match self.file_info.tex_type {
+            TextureType::CubemapArray => {
+                let cube_count = self.cube_count()?;
+                (0, if cube_count > 1 { cube_count } else { 0 }, 6)
+            }
+            TextureType::D3 if total_levels > 1 => return Err(BasisError::UnsupportedTextureType(TextureType::D3)),
+            TextureType::D3 => (total_images, 0, 1),
+            _ if total_images > 1 => (0, total_images, 1),
+            _ => (0, 0, 1),
+        };
+
+        let mut levels = Vec::with_capacity(total_levels as usize);
+        for level_index in 0..total_levels {
+            let level_info = self.level_info(0, level_index)?;
+
+            let images = (0..total_images)
+                .map(|image_index| self.transcode_image_level(image_index, level_index, format, decode_flags))
+                .collect::<Result<_, _>>()?;
+
+            levels.push(ExportedLevel {
+                width: level_info.orig_width,
+                height: level_info.orig_height,
+                images,
+            });
+        }
+
+        Ok(ExportedTexture {
+            width: level_info.orig_width,
+            height: level_info.orig_height,
+            depth,
+            layers,
+            faces,
+            levels,
+        })
+    }
+}
+
+/// A basic data format descriptor block, preceded by the total size of the descriptor.
+fn data_format_descriptor(format: TargetTextureFormat, dfd_format: &DfdFormat, srgb: bool) -> Vec<u8> {
+    let block_size = 24 + 16 * dfd_format.samples.len();
+    let texel_block_dimension = if format.is_uncompressed() { 0 } else { 3 };
+
+    let mut out = Vec::with_capacity(4 + block_size);
+    put_u32(&mut out, 4 + block_size as u32);
+    // Khronos vendor, basic descriptor type.
+    put_u32(&mut out, 0);
+    put_u16(&mut out, 2);
+    put_u16(&mut out, block_size as u16);
+    out.extend_from_slice(&[
+        dfd_format.color_model,
+        KHR_DF_PRIMARIES_BT709,
+        if srgb {
+            KHR_DF_TRANSFER_SRGB
+        } else {
+            KHR_DF_TRANSFER_LINEAR
+        },
+        // Straight alpha.
+        0,
+        texel_block_dimension,
+        texel_block_dimension,
+        0,
+        0,
+        format.bytes_per_texel_block() as u8,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+    ]);
+
+    let mut bit_offset = 0;
+    for &(channel, bit_length) in dfd_format.samples {
+        // Alpha is never sRGB encoded.
+        let qualifiers = if srgb && channel == KHR_DF_CHANNEL_ALPHA {
+            KHR_DF_SAMPLE_DATATYPE_LINEAR
+        } else {
+            0
+        };
+        #[allow(clippy::legacy_numeric_constants)] // msrv doesn't allow this
+        let upper = if format.is_uncompressed() {
+            (1 << bit_length) - 1
+        } else {
+            u32::max_value()
+        };
+
+        put_u16(&mut out, bit_offset);
+        out.extend_from_slice(&[bit_length - 1, channel | qualifiers, 0, 0, 0, 0]);
+        put_u32(&mut out, 0);
+        put_u32(&mut out, upper);
+
+        bit_offset += u16::from(bit_length);
+    }
+
+    out
+}
+
+fn put_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+fn pad(out: &mut Vec<u8>, alignment: usize) {
+    out.resize(round_up(out.len(), alignment), 0);
+}
+
+#[allow(clippy::manual_div_ceil)] // msrv doesn't allow this
+fn round_up(value: usize, alignment: usize) -> usize {
+    (value + alignment - 1) / alignment * alignment
+}
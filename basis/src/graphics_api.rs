@@ -0,0 +1,131 @@
+use crate::TargetTextureFormat;
+
+impl TargetTextureFormat {
+    /// `VkFormat` holding this format's blocks, or `None` for ATC and FXT1, which Vulkan doesn't support.
+    ///
+    /// `srgb` picks the sRGB variant where there is one. PVRTC formats come from `VK_IMG_format_pvrtc`.
+    pub fn vk_format(self, srgb: bool) -> Option<u32> {
+        let (unorm, srgb_format) = match self {
+            // ETC1 is a subset of ETC2.
+            Self::Etc1Rgb => (147, Some(148)),
+            Self::Etc2Rgba => (151, Some(152)),
+            Self::Bc1Rgb => (131, Some(132)),
+            Self::Bc3Rgba => (137, Some(138)),
+            Self::Bc4R => (139, None),
+            Self::Bc5Rg => (141, None),
+            Self::Bc7Rgba => (145, Some(146)),
+            Self::Pvrtc1Rgb | Self::Pvrtc1Rgba => (1_000_054_001, Some(1_000_054_005)),
+            Self::Pvrtc2Rgb | Self::Pvrtc2Rgba => (1_000_054_003, Some(1_000_054_007)),
+            Self::AstcRgba => (157, Some(158)),
+            Self::EacR11 => (153, None),
+            Self::EacRg11 => (155, None),
+            Self::Rgba32 => (37, Some(43)),
+            Self::Rgb565 => (4, None),
+            Self::Bgr565 => (5, None),
+            Self::Rgba4444 => (2, None),
+            Self::AtcRgb | Self::AtcRgbA | Self::Fxt1Rgb => return None,
+        };
+
+        Some(pick_variant(unorm, srgb_format, srgb))
+    }
+
+    /// `DXGI_FORMAT` holding this format's blocks, or `None` if D3D can't sample it.
+    ///
+    /// D3D only supports the BC formats, RGBA32 and RGB565. BGR565 and RGBA4444 have no DXGI format with
+    /// the same channel order.
+    pub fn dxgi_format(self, srgb: bool) -> Option<u32> {
+        let (unorm, srgb_format) = match self {
+            Self::Bc1Rgb => (71, Some(72)),
+            Self::Bc3Rgba => (77, Some(78)),
+            Self::Bc4R => (80, None),
+            Self::Bc5Rg => (83, None),
+            Self::Bc7Rgba => (98, Some(99)),
+            Self::Rgba32 => (28, Some(29)),
+            // DXGI names channels from the least significant bit, so red in the top bits is B5G6R5.
+            Self::Rgb565 => (85, None),
+            Self::Etc1Rgb
+            | Self::Etc2Rgba
+            | Self::Pvrtc1Rgb
+            | Self::Pvrtc1Rgba
+            | Self::AstcRgba
+            | Self::AtcRgb
+            | Self::AtcRgbA
+            | Self::Fxt1Rgb
+            | Self::Pvrtc2Rgb
+            | Self::Pvrtc2Rgba
+            | Self::EacR11
+            | Self::EacRg11
+            | Self::Bgr565
+            | Self::Rgba4444 => return None,
+        };
+
+        Some(pick_variant(unorm, srgb_format, srgb))
+    }
+
+    /// OpenGL internal format holding this format's blocks. Every target currently has one, though most
+    /// compressed formats need an extension.
+    ///
+    /// RGB565 and BGR565 share `GL_RGB565`, and are told apart by the pixel type they are uploaded with.
+    pub fn gl_internal_format(self, srgb: bool) -> Option<u32> {
+        let (unorm, srgb_format) = match self {
+            Self::Etc1Rgb => (0x8D64, Some(0x9275)),
+            Self::Etc2Rgba => (0x9278, Some(0x9279)),
+            Self::Bc1Rgb => (0x83F0, Some(0x8C4C)),
+            Self::Bc3Rgba => (0x83F3, Some(0x8C4F)),
+            Self::Bc4R => (0x8DBB, None),
+            Self::Bc5Rg => (0x8DBD, None),
+            Self::Bc7Rgba => (0x8E8C, Some(0x8E8D)),
+            Self::Pvrtc1Rgb => (0x8C00, Some(0x8A55)),
+            Self::Pvrtc1Rgba => (0x8C02, Some(0x8A57)),
+            Self::AstcRgba => (0x93B0, Some(0x93D0)),
+            Self::AtcRgb => (0x8C92, None),
+            Self::AtcRgbA => (0x87EE, None),
+            Self::Fxt1Rgb => (0x86B0, None),
+            Self::Pvrtc2Rgb | Self::Pvrtc2Rgba => (0x9138, Some(0x93F1)),
+            Self::EacR11 => (0x9270, None),
+            Self::EacRg11 => (0x9272, None),
+            Self::Rgba32 => (0x8058, Some(0x8C43)),
+            Self::Rgb565 | Self::Bgr565 => (0x8D62, None),
+            Self::Rgba4444 => (0x8056, None),
+        };
+
+        Some(pick_variant(unorm, srgb_format, srgb))
+    }
+
+    /// `MTLPixelFormat` holding this format's blocks, or `None` if Metal can't sample it.
+    ///
+    /// Metal has no PVRTC2, ATC, FXT1 or BGR565 formats. BC formats need a Mac, while PVRTC, ETC, EAC, ASTC,
+    /// RGB565 and RGBA4444 need an Apple GPU.
+    pub fn metal_pixel_format(self, srgb: bool) -> Option<u64> {
+        let (unorm, srgb_format) = match self {
+            Self::Etc1Rgb => (180, Some(181)),
+            Self::Etc2Rgba => (178, Some(179)),
+            Self::Bc1Rgb => (130, Some(131)),
+            Self::Bc3Rgba => (134, Some(135)),
+            Self::Bc4R => (140, None),
+            Self::Bc5Rg => (142, None),
+            Self::Bc7Rgba => (152, Some(153)),
+            Self::Pvrtc1Rgb => (162, Some(163)),
+            Self::Pvrtc1Rgba => (166, Some(167)),
+            Self::AstcRgba => (204, Some(186)),
+            Self::EacR11 => (170, None),
+            Self::EacRg11 => (174, None),
+            Self::Rgba32 => (70, Some(71)),
+            // Metal names packed channels from the least significant bit.
+            Self::Rgb565 => (40, None),
+            Self::Rgba4444 => (42, None),
+            Self::AtcRgb | Self::AtcRgbA | Self::Fxt1Rgb | Self::Pvrtc2Rgb | Self::Pvrtc2Rgba | Self::Bgr565 => {
+                return None
+            }
+        };
+
+        Some(pick_variant(unorm, srgb_format, srgb))
+    }
+}
+
+fn pick_variant<T>(unorm: T, srgb_format: Option<T>, srgb: bool) -> T {
+    match srgb_format {
+        Some(srgb_format) if srgb => srgb_format,
+        _ => unorm,
+    }
+}
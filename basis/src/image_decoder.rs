@@ -0,0 +1,91 @@
+use crate::{BasisError, DecodeFlags, PreparedBasisFile, TargetTextureFormat, Transcoder};
+use image::{
+    error::{DecodingError, ImageFormatHint},
+    ColorType, ImageDecoder, ImageError, ImageResult, RgbaImage,
+};
+use std::io::{Cursor, Read};
+
+impl<'a> PreparedBasisFile<'a> {
+    /// Transcodes an image level to [`TargetTextureFormat::Rgba32`], cropped to its original resolution.
+    pub fn transcode_rgba_image(
+        &mut self,
+        image_index: u32,
+        level_index: u32,
+        decode_flags: DecodeFlags,
+    ) -> Result<RgbaImage, BasisError> {
+        let level_info = self.level_info(image_index, level_index)?;
+        let data = self.transcode_image_level(image_index, level_index, TargetTextureFormat::Rgba32, decode_flags)?;
+
+        RgbaImage::from_raw(level_info.orig_width, level_info.orig_height, data).ok_or(BasisError::TranscodeFailed)
+    }
+}
+
+/// Decodes a single image level of a `.basis` file through the `image` crate.
+///
+/// ```no_run
+/// let file = std::fs::File::open("texture.basis").unwrap();
+/// let decoder = basis::BasisDecoder::new(file).unwrap();
+/// let image = basis::image::DynamicImage::from_decoder(decoder).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct BasisDecoder {
+    file: Vec<u8>,
+    image_index: u32,
+    level_index: u32,
+    width: u32,
+    height: u32,
+}
+impl BasisDecoder {
+    /// Reads the whole file and decodes the first level of its first image.
+    pub fn new<R: Read>(reader: R) -> ImageResult<Self> {
+        Self::with_image_level(reader, 0, 0)
+    }
+
+    /// Reads the whole file and decodes the given image level.
+    pub fn with_image_level<R: Read>(mut reader: R, image_index: u32, level_index: u32) -> ImageResult<Self> {
+        let mut file = Vec::new();
+        reader.read_to_end(&mut file)?;
+
+        let level_info = Transcoder::new()
+            .get_basic_image_level_info(&file, image_index, level_index)
+            .map_err(decoding_error)?;
+
+        Ok(Self {
+            file,
+            image_index,
+            level_index,
+            width: level_info.orig_width,
+            height: level_info.orig_height,
+        })
+    }
+}
+impl<'a> ImageDecoder<'a> for BasisDecoder {
+    type Reader = Cursor<Vec<u8>>;
+
+    fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn color_type(&self) -> ColorType {
+        ColorType::Rgba8
+    }
+
+    fn into_reader(self) -> ImageResult<Self::Reader> {
+        let transcoder = Transcoder::new();
+        let mut prepared = transcoder.prepare_transcoding(&self.file).map_err(decoding_error)?;
+        let data = prepared
+            .transcode_image_level(
+                self.image_index,
+                self.level_index,
+                TargetTextureFormat::Rgba32,
+                DecodeFlags::empty(),
+            )
+            .map_err(decoding_error)?;
+
+        Ok(Cursor::new(data))
+    }
+}
+
+fn decoding_error(error: BasisError) -> ImageError {
+    ImageError::Decoding(DecodingError::new(ImageFormatHint::Name("basis".into()), error))
+}
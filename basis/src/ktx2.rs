@@ -0,0 +1,407 @@
+use crate::{
+    init, read_vector_u8, sys, validate_slice_length, BasicImageLevelInfo, BasisError, BasisTextureFormat, DecodeFlags,
+    LevelShape, TargetTextureFormat,
+};
+use std::{ptr, str};
+
+/// `KHR_DF_TRANSFER_SRGB` from the Khronos Data Format Specification.
+const KHR_DF_TRANSFER_SRGB: u32 = 2;
+/// `KHR_DF_FLAG_ALPHA_PREMULTIPLIED` from the Khronos Data Format Specification.
+const KHR_DF_FLAG_ALPHA_PREMULTIPLIED: u32 = 1;
+
+/// Color information from the data format descriptor (DFD) of a KTX2 file.
+///
+/// Values are the raw `KHR_DF_*` enums of the Khronos Data Format Specification.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Ktx2ColorInfo {
+    pub color_model: u32,
+    pub color_primaries: u32,
+    pub transfer_function: u32,
+    pub flags: u32,
+    pub total_samples: u32,
+    /// Channel of the first and second sample. For ETC1S, these say whether the file has alpha slices;
+    /// for UASTC, whether it is RGB, RGBA, RRR or RRRG.
+    pub channel_ids: [u32; 2],
+}
+impl Ktx2ColorInfo {
+    pub fn is_srgb(&self) -> bool {
+        self.transfer_function == KHR_DF_TRANSFER_SRGB
+    }
+
+    pub fn is_alpha_premultiplied(&self) -> bool {
+        self.flags & KHR_DF_FLAG_ALPHA_PREMULTIPLIED != 0
+    }
+}
+
+/// An entry of a KTX2 file's key/value data, like `KTXorientation` or `KTXwriter`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Ktx2KeyValue<'t> {
+    pub key: &'t str,
+    pub value: &'t [u8],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ktx2ImageLevelInfo {
+    pub level_index: u32,
+    pub layer_index: u32,
+    pub face_index: u32,
+    pub orig_width: u32,
+    pub orig_height: u32,
+    pub width: u32,
+    pub height: u32,
+    pub num_blocks_x: u32,
+    pub num_blocks_y: u32,
+    pub total_blocks: u32,
+    pub alpha_flag: bool,
+    pub iframe_flag: bool,
+}
+impl Ktx2ImageLevelInfo {
+    fn from_internal(value: sys::ktx2_image_level_info) -> Self {
+        Self {
+            level_index: value.m_level_index,
+            layer_index: value.m_layer_index,
+            face_index: value.m_face_index,
+            orig_width: value.m_orig_width,
+            orig_height: value.m_orig_height,
+            width: value.m_width,
+            height: value.m_height,
+            num_blocks_x: value.m_num_blocks_x,
+            num_blocks_y: value.m_num_blocks_y,
+            total_blocks: value.m_total_blocks,
+            alpha_flag: value.m_alpha_flag,
+            iframe_flag: value.m_iframe_flag,
+        }
+    }
+}
+
+/// Transcoder for `.ktx2` files holding ETC1S (BasisLZ) or UASTC data, optionally supercompressed
+/// with Zstandard.
+///
+/// Unlike [`Transcoder`](crate::Transcoder), a KTX2 transcoder is bound to a single file, as it parses
+/// the file's header and indices up front.
+pub struct Ktx2Transcoder<'a> {
+    inner: *mut sys::ktx2_transcoder,
+    file: &'a [u8],
+    basis_format: BasisTextureFormat,
+    started: bool,
+}
+impl<'a> Ktx2Transcoder<'a> {
+    pub fn new(file: &'a [u8]) -> Result<Self, BasisError> {
+        init();
+
+        let length = validate_slice_length(file)?;
+
+        let inner = unsafe { sys::basisrs_create_ktx2_transcoder() };
+
+        let res = unsafe { sys::basisrs_ktx2_init(inner, file.as_ptr() as _, length) };
+
+        if !res {
+            unsafe { sys::basisrs_destroy_ktx2_transcoder(inner) };
+            return Err(BasisError::InvalidHeader);
+        }
+
+        let basis_format = match BasisTextureFormat::from_internal(unsafe { sys::basisrs_ktx2_get_format(inner) }) {
+            Ok(basis_format) => basis_format,
+            Err(e) => {
+                unsafe { sys::basisrs_destroy_ktx2_transcoder(inner) };
+                return Err(e);
+            }
+        };
+
+        Ok(Self {
+            inner,
+            file,
+            basis_format,
+            started: false,
+        })
+    }
+
+    /// The file this transcoder was created with.
+    pub fn file(&self) -> &'a [u8] {
+        self.file
+    }
+
+    /// Width of the first level in pixels.
+    pub fn get_width(&self) -> u32 {
+        unsafe { sys::basisrs_ktx2_get_width(self.inner) }
+    }
+
+    /// Height of the first level in pixels.
+    pub fn get_height(&self) -> u32 {
+        unsafe { sys::basisrs_ktx2_get_height(self.inner) }
+    }
+
+    pub fn get_total_levels(&self) -> u32 {
+        unsafe { sys::basisrs_ktx2_get_levels(self.inner) }
+    }
+
+    /// Number of array layers, or 1 if the texture isn't an array.
+    pub fn get_total_layers(&self) -> u32 {
+        unsafe { sys::basisrs_ktx2_get_layers(self.inner) }.max(1)
+    }
+
+    pub fn is_array(&self) -> bool {
+        unsafe { sys::basisrs_ktx2_get_layers(self.inner) != 0 }
+    }
+
+    /// 6 for cubemaps, 1 otherwise.
+    pub fn get_total_faces(&self) -> u32 {
+        unsafe { sys::basisrs_ktx2_get_faces(self.inner) }
+    }
+
+    pub fn get_tex_format(&self) -> BasisTextureFormat {
+        self.basis_format
+    }
+
+    pub fn has_alpha(&self) -> bool {
+        unsafe { sys::basisrs_ktx2_get_has_alpha(self.inner) }
+    }
+
+    /// Whether the file is an ETC1S video, where P-frames must be transcoded in order after their I-frame.
+    pub fn is_video(&self) -> bool {
+        unsafe { sys::basisrs_ktx2_is_video(self.inner) }
+    }
+
+    pub fn get_color_info(&self) -> Ktx2ColorInfo {
+        unsafe {
+            Ktx2ColorInfo {
+                color_model: sys::basisrs_ktx2_get_dfd_color_model(self.inner),
+                color_primaries: sys::basisrs_ktx2_get_dfd_color_primaries(self.inner),
+                transfer_function: sys::basisrs_ktx2_get_dfd_transfer_func(self.inner),
+                flags: sys::basisrs_ktx2_get_dfd_flags(self.inner),
+                total_samples: sys::basisrs_ktx2_get_dfd_total_samples(self.inner),
+                channel_ids: [
+                    sys::basisrs_ktx2_get_dfd_channel_id0(self.inner),
+                    sys::basisrs_ktx2_get_dfd_channel_id1(self.inner),
+                ],
+            }
+        }
+    }
+
+    /// Every entry of the file's key/value data, in file order.
+    ///
+    /// Returns [`BasisError::InvalidFile`] if a key isn't UTF-8.
+    pub fn get_key_values(&self) -> Result<Vec<Ktx2KeyValue<'_>>, BasisError> {
+        let total = unsafe { sys::basisrs_ktx2_get_total_key_values(self.inner) };
+
+        let mut vec = Vec::with_capacity(total as _);
+        for index in 0..total {
+            let mut key = sys::basisrs_vector_u8 {
+                values: ptr::null(),
+                size: 0,
+            };
+            let mut value = key;
+
+            let res = unsafe { sys::basisrs_ktx2_get_key_value(self.inner, index, &mut key, &mut value) };
+            if !res {
+                return Err(BasisError::InvalidFile);
+            }
+
+            let key = unsafe { read_vector_u8(key) };
+            // Keys are stored with their null terminator.
+            let key = key.split(|&b| b == 0).next().unwrap_or(key);
+
+            vec.push(Ktx2KeyValue {
+                key: str::from_utf8(key).map_err(|_| BasisError::InvalidFile)?,
+                value: unsafe { read_vector_u8(value) },
+            });
+        }
+        Ok(vec)
+    }
+
+    /// Value of the given key, if the file has it.
+    pub fn get_key_value(&self, key: &str) -> Result<Option<&[u8]>, BasisError> {
+        Ok(self
+            .get_key_values()?
+            .into_iter()
+            .find(|key_value| key_value.key == key)
+            .map(|key_value| key_value.value))
+    }
+
+    /// Basic information about a level of a face of a layer, in the shape [`Transcoder`](crate::Transcoder)
+    /// returns it.
+    pub fn get_basic_image_level_info(
+        &self,
+        level_index: u32,
+        layer_index: u32,
+        face_index: u32,
+    ) -> Result<BasicImageLevelInfo, BasisError> {
+        let level_info = self.get_image_level_info(level_index, layer_index, face_index)?;
+
+        Ok(BasicImageLevelInfo {
+            orig_width: level_info.orig_width,
+            orig_height: level_info.orig_height,
+            total_blocks: level_info.total_blocks,
+        })
+    }
+
+    pub fn get_image_level_info(
+        &self,
+        level_index: u32,
+        layer_index: u32,
+        face_index: u32,
+    ) -> Result<Ktx2ImageLevelInfo, BasisError> {
+        self.check_image(level_index, layer_index, face_index)?;
+
+        let mut data = sys::ktx2_image_level_info {
+            m_level_index: 0,
+            m_layer_index: 0,
+            m_face_index: 0,
+            m_orig_width: 0,
+            m_orig_height: 0,
+            m_width: 0,
+            m_height: 0,
+            m_num_blocks_x: 0,
+            m_num_blocks_y: 0,
+            m_total_blocks: 0,
+            m_alpha_flag: false,
+            m_iframe_flag: false,
+        };
+
+        let res = unsafe {
+            sys::basisrs_ktx2_get_image_level_info(self.inner, &mut data, level_index, layer_index, face_index)
+        };
+
+        if res {
+            Ok(Ktx2ImageLevelInfo::from_internal(data))
+        } else {
+            Err(BasisError::InvalidFile)
+        }
+    }
+
+    /// Number of bytes needed to hold the given level transcoded to `format`.
+    pub fn transcoded_size(
+        &self,
+        level_index: u32,
+        layer_index: u32,
+        face_index: u32,
+        format: TargetTextureFormat,
+    ) -> Result<usize, BasisError> {
+        let level_info = self.get_basic_image_level_info(level_index, layer_index, face_index)?;
+
+        LevelShape::new(&level_info, format).total_bytes(0)
+    }
+
+    pub fn transcode_image_level(
+        &mut self,
+        level_index: u32,
+        layer_index: u32,
+        face_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+    ) -> Result<Vec<u8>, BasisError> {
+        let mut result = vec![0; self.transcoded_size(level_index, layer_index, face_index, format)?];
+
+        self.transcode_image_level_into(level_index, layer_index, face_index, format, decode_flags, &mut result)?;
+
+        Ok(result)
+    }
+
+    /// Transcodes the given level into `output` instead of allocating a new buffer.
+    ///
+    /// `output` must be at least [`transcoded_size`](Self::transcoded_size) bytes long.
+    /// Returns the number of bytes written to the front of `output`.
+    pub fn transcode_image_level_into(
+        &mut self,
+        level_index: u32,
+        layer_index: u32,
+        face_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+        output: &mut [u8],
+    ) -> Result<usize, BasisError> {
+        if !self.basis_format.supports_texture_format(format) {
+            return Err(BasisError::UnsupportedTarget {
+                basis_format: self.basis_format,
+                target: format,
+            });
+        }
+
+        let level_info = self.get_basic_image_level_info(level_index, layer_index, face_index)?;
+
+        let shape = LevelShape::new(&level_info, format);
+        let total_units = shape.total_units(0)?;
+        let required = shape.total_bytes(0)?;
+
+        if output.len() < required {
+            return Err(BasisError::BufferTooSmall {
+                required,
+                provided: output.len(),
+            });
+        }
+
+        self.start_transcoding()?;
+
+        let res = unsafe {
+            sys::basisrs_ktx2_transcode_image_level(
+                self.inner,
+                level_index,
+                layer_index,
+                face_index,
+                output.as_mut_ptr() as _,
+                total_units,
+                format.as_internal(),
+                decode_flags.bits(),
+                0,
+                0,
+            )
+        };
+
+        if res {
+            Ok(required)
+        } else {
+            Err(BasisError::TranscodeFailed)
+        }
+    }
+
+    /// Decompresses the ETC1S codebooks. Done once, before the first level is transcoded.
+    fn start_transcoding(&mut self) -> Result<(), BasisError> {
+        if !self.started {
+            let res = unsafe { sys::basisrs_ktx2_start_transcoding(self.inner) };
+
+            if !res {
+                return Err(BasisError::InvalidFile);
+            }
+
+            self.started = true;
+        }
+
+        Ok(())
+    }
+
+    fn check_image(&self, level_index: u32, layer_index: u32, face_index: u32) -> Result<(), BasisError> {
+        let total_layers = self.get_total_layers();
+        if layer_index >= total_layers {
+            return Err(BasisError::LayerOutOfRange {
+                layer_index,
+                total_layers,
+            });
+        }
+
+        let total_faces = self.get_total_faces();
+        if face_index >= total_faces {
+            return Err(BasisError::FaceOutOfRange {
+                face_index,
+                total_faces,
+            });
+        }
+
+        let total_levels = self.get_total_levels();
+        if level_index >= total_levels {
+            return Err(BasisError::LevelOutOfRange {
+                image_index: layer_index,
+                level_index,
+                total_levels,
+            });
+        }
+
+        Ok(())
+    }
+}
+impl<'a> Drop for Ktx2Transcoder<'a> {
+    fn drop(&mut self) {
+        unsafe { sys::basisrs_destroy_ktx2_transcoder(self.inner) };
+    }
+}
+
+unsafe impl<'a> Send for Ktx2Transcoder<'a> {}
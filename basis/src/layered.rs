@@ -0,0 +1,188 @@
+use crate::{BasisError, DecodeFlags, PreparedBasisFile, TargetTextureFormat, TextureType};
+
+/// Faces of a cubemap, in the order they are stored in the file.
+///
+/// This is also the layer order D3D, Vulkan, OpenGL and Metal expect for cube textures.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::PositiveX,
+        CubeFace::NegativeX,
+        CubeFace::PositiveY,
+        CubeFace::NegativeY,
+        CubeFace::PositiveZ,
+        CubeFace::NegativeZ,
+    ];
+
+    /// Position of the face within a cube.
+    pub fn index(self) -> u32 {
+        self as u32
+    }
+
+    /// Index of the image holding this face of the given cube.
+    pub fn image_index(self, cube_index: u32) -> u32 {
+        cube_index * 6 + self.index()
+    }
+}
+
+/// A single mip level of several images with the same resolution, transcoded one after another into one buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayeredLevel {
+    pub level_index: u32,
+    /// Width of every layer in pixels.
+    pub width: u32,
+    /// Height of every layer in pixels.
+    pub height: u32,
+    pub layer_count: u32,
+    /// Bytes between the start of two consecutive layers.
+    pub layer_stride: usize,
+    pub data: Vec<u8>,
+}
+impl LayeredLevel {
+    pub fn layer(&self, layer: u32) -> Result<&[u8], BasisError> {
+        if layer >= self.layer_count {
+            return Err(BasisError::LayerOutOfRange {
+                layer_index: layer,
+                total_layers: self.layer_count,
+            });
+        }
+
+        let start = layer as usize * self.layer_stride;
+        Ok(&self.data[start..start + self.layer_stride])
+    }
+
+    /// Data of a single face of a cube, where `cube` counts from the first cube that was transcoded.
+    pub fn face(&self, cube: u32, face: CubeFace) -> Result<&[u8], BasisError> {
+        if cube >= self.layer_count / 6 {
+            return Err(BasisError::FaceOutOfRange {
+                face_index: cube.saturating_mul(6).saturating_add(face.index()),
+                total_faces: self.layer_count,
+            });
+        }
+
+        self.layer(face.image_index(cube))
+    }
+}
+
+impl<'a> PreparedBasisFile<'a> {
+    /// Number of cubes in a [`TextureType::CubemapArray`] file.
+    #[allow(clippy::manual_is_multiple_of)] // msrv doesn't allow this
+    pub fn cube_count(&self) -> Result<u32, BasisError> {
+        let file_info = &self.file_info;
+
+        if file_info.tex_type != TextureType::CubemapArray {
+            return Err(BasisError::UnsupportedTextureType(file_info.tex_type));
+        }
+
+        if file_info.total_images % 6 != 0 {
+            return Err(BasisError::InvalidCubemapImageCount(file_info.total_images));
+        }
+
+        Ok(file_info.total_images / 6)
+    }
+
+    /// Transcodes the six faces of a single cube at the given level, in [`CubeFace`] order.
+    pub fn transcode_cube(
+        &mut self,
+        cube_index: u32,
+        level_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+    ) -> Result<LayeredLevel, BasisError> {
+        let cube_count = self.cube_count()?;
+
+        if cube_index >= cube_count {
+            return Err(BasisError::ImageOutOfRange {
+                image_index: CubeFace::PositiveX.image_index(cube_index),
+                total_images: cube_count * 6,
+            });
+        }
+
+        self.transcode_layers(
+            CubeFace::PositiveX.image_index(cube_index),
+            6,
+            level_index,
+            format,
+            decode_flags,
+        )
+    }
+
+    /// Transcodes every face of every cube at the given level, ready to be uploaded as a cube array.
+    pub fn transcode_cube_array(
+        &mut self,
+        level_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+    ) -> Result<LayeredLevel, BasisError> {
+        let cube_count = self.cube_count()?;
+
+        self.transcode_layers(0, cube_count * 6, level_index, format, decode_flags)
+    }
+
+    /// Transcodes a level of every layer of a [`TextureType::D2Array`] file, or every depth slice of a
+    /// [`TextureType::D3`] file, into a single buffer.
+    ///
+    /// Volumes store every depth slice at every level, so the depth does not shrink with the level like a
+    /// GPU volume texture's would.
+    pub fn transcode_layered_level(
+        &mut self,
+        level_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+    ) -> Result<LayeredLevel, BasisError> {
+        match self.file_info.tex_type {
+            TextureType::D2Array | TextureType::D3 => {}
+            tex_type => return Err(BasisError::UnsupportedTextureType(tex_type)),
+        }
+
+        self.transcode_layers(0, self.file_info.total_images, level_index, format, decode_flags)
+    }
+
+    /// Transcodes a level of consecutive images, making sure they all have the same resolution and level count.
+    fn transcode_layers(
+        &mut self,
+        first_image: u32,
+        layer_count: u32,
+        level_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+    ) -> Result<LayeredLevel, BasisError> {
+        let level_info = self.level_info(first_image, level_index)?;
+        let total_levels = self.file_info.image_mipmap_levels[first_image as usize];
+
+        for image_index in first_image + 1..first_image + layer_count {
+            let image_level_info = self.level_info(image_index, level_index)?;
+
+            if self.file_info.image_mipmap_levels[image_index as usize] != total_levels
+                || image_level_info.orig_width != level_info.orig_width
+                || image_level_info.orig_height != level_info.orig_height
+            {
+                return Err(BasisError::InconsistentImages { image_index });
+            }
+        }
+
+        let layer_stride = self.transcoded_size(first_image, level_index, format)?;
+        let mut data = vec![0; layer_stride * layer_count as usize];
+
+        for (image_index, layer) in (first_image..).zip(data.chunks_mut(layer_stride)) {
+            self.transcode_image_level_into(image_index, level_index, format, decode_flags, layer)?;
+        }
+
+        Ok(LayeredLevel {
+            level_index,
+            width: level_info.orig_width,
+            height: level_info.orig_height,
+            layer_count,
+            layer_stride,
+            data,
+        })
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{BasisError, DecodeFlags, LevelShape, PreparedBasisFile, TargetTextureFormat};
+use std::ptr;
+
+/// Alignment rules used to lay out every image and level of a file in a single staging buffer.
+///
+/// ```no_run
+/// # let file = std::fs::read("texture.basis").unwrap();
+/// let transcoder = basis::Transcoder::new();
+/// let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+///
+/// // D3D12 texture copy requirements
+/// let layout = basis::StagingLayoutBuilder::new()
+///     .row_pitch_alignment(256)
+///     .subresource_alignment(512)
+///     .build(&prepared, basis::TargetTextureFormat::Bc7Rgba)
+///     .unwrap();
+///
+/// let mut staging = vec![0; layout.size];
+/// prepared
+///     .transcode_into_layout(&layout, basis::DecodeFlags::empty(), &mut staging)
+///     .unwrap();
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StagingLayoutBuilder {
+    row_pitch_alignment: usize,
+    subresource_alignment: usize,
+}
+impl StagingLayoutBuilder {
+    pub fn new() -> Self {
+        Self {
+            row_pitch_alignment: 1,
+            subresource_alignment: 1,
+        }
+    }
+
+    /// Alignment in bytes of every row of blocks (or pixels for uncompressed formats). Must be non-zero.
+    ///
+    /// PVRTC1 output is always tightly packed, as the transcoder ignores the row pitch for it.
+    pub fn row_pitch_alignment(mut self, alignment: usize) -> Self {
+        self.row_pitch_alignment = alignment;
+        self
+    }
+
+    /// Alignment in bytes of the start of every image level. Must be non-zero.
+    pub fn subresource_alignment(mut self, alignment: usize) -> Self {
+        self.subresource_alignment = alignment;
+        self
+    }
+
+    /// Lays out every level of every image in the file, ordered by image then level.
+    ///
+    /// Offsets and row pitches are additionally aligned to the size of a block (or pixel), as
+    /// graphics APIs require.
+    pub fn build(
+        &self,
+        prepared: &PreparedBasisFile<'_>,
+        format: TargetTextureFormat,
+    ) -> Result<StagingLayout, BasisError> {
+        if self.row_pitch_alignment == 0 {
+            return Err(BasisError::InvalidParameter("row_pitch_alignment"));
+        }
+        if self.subresource_alignment == 0 {
+            return Err(BasisError::InvalidParameter("subresource_alignment"));
+        }
+
+        let block_width = if format.is_uncompressed() {
+            1
+        } else if format == TargetTextureFormat::Fxt1Rgb {
+            8
+        } else {
+            4
+        };
+
+        let mut subresources = Vec::new();
+        let mut size = 0;
+
+        for (image_index, &total_levels) in (0..).zip(&prepared.file_info.image_mipmap_levels) {
+            for level_index in 0..total_levels {
+                let level_info = prepared.level_info(image_index, level_index)?;
+                let shape = LevelShape::new(&level_info, format);
+
+                let tight_pitch = shape.row_units as usize * shape.bytes_per_unit;
+                let row_pitch = if is_pvrtc1(format) {
+                    tight_pitch
+                } else {
+                    align(tight_pitch, lcm(self.row_pitch_alignment, shape.bytes_per_unit))
+                };
+                let offset = align(size, lcm(self.subresource_alignment, shape.bytes_per_unit));
+                let level_size = row_pitch * shape.rows as usize;
+
+                subresources.push(Subresource {
+                    image_index,
+                    level_index,
+                    width: level_info.orig_width,
+                    height: level_info.orig_height,
+                    offset,
+                    row_pitch,
+                    rows: shape.rows,
+                    size: level_size,
+                    row_pitch_units: (row_pitch / shape.bytes_per_unit) as u32,
+                    block_width,
+                });
+
+                size = offset + level_size;
+            }
+        }
+
+        Ok(StagingLayout {
+            format,
+            size,
+            subresources,
+        })
+    }
+}
+
+impl Default for StagingLayoutBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Placement of every image level of a file inside a single staging buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagingLayout {
+    pub format: TargetTextureFormat,
+    /// Total size of the staging buffer in bytes.
+    pub size: usize,
+    /// One entry per image level, ordered by image then level.
+    pub subresources: Vec<Subresource>,
+}
+impl StagingLayout {
+    pub fn subresource(&self, image_index: u32, level_index: u32) -> Option<&Subresource> {
+        self.subresources
+            .iter()
+            .find(|s| s.image_index == image_index && s.level_index == level_index)
+    }
+}
+
+/// Placement of a single image level inside a [`StagingLayout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subresource {
+    pub image_index: u32,
+    pub level_index: u32,
+    /// Width of the level in pixels.
+    pub width: u32,
+    /// Height of the level in pixels.
+    pub height: u32,
+    /// Offset of the first row in bytes from the start of the staging buffer.
+    pub offset: usize,
+    /// Distance in bytes between the start of two consecutive rows.
+    pub row_pitch: usize,
+    /// Number of rows of blocks, or rows of pixels for uncompressed formats.
+    pub rows: u32,
+    /// Size of the level in bytes, including row padding.
+    pub size: usize,
+    row_pitch_units: u32,
+    block_width: u32,
+}
+impl Subresource {
+    /// Row pitch in pixels, as Vulkan's `bufferRowLength` expects.
+    pub fn row_length(&self) -> u32 {
+        self.row_pitch_units * self.block_width
+    }
+}
+
+impl<'a> PreparedBasisFile<'a> {
+    /// Transcodes every image level into its place in `layout`.
+    ///
+    /// `output` must be at least `layout.size` bytes long. Row padding is left untouched.
+    pub fn transcode_into_layout(
+        &mut self,
+        layout: &StagingLayout,
+        decode_flags: DecodeFlags,
+        output: &mut [u8],
+    ) -> Result<(), BasisError> {
+        if output.len() < layout.size {
+            return Err(BasisError::BufferTooSmall {
+                required: layout.size,
+                provided: output.len(),
+            });
+        }
+
+        for subresource in &layout.subresources {
+            let destination = &mut output[subresource.offset..subresource.offset + subresource.size];
+            let row_pitch = if is_pvrtc1(layout.format) {
+                0
+            } else {
+                subresource.row_pitch_units
+            };
+
+            unsafe {
+                self.transcode_image_level_raw(
+                    ptr::null_mut(),
+                    subresource.image_index,
+                    subresource.level_index,
+                    layout.format,
+                    decode_flags,
+                    row_pitch,
+                    destination.as_mut_ptr(),
+                    destination.len(),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_pvrtc1(format: TargetTextureFormat) -> bool {
+    format == TargetTextureFormat::Pvrtc1Rgb || format == TargetTextureFormat::Pvrtc1Rgba
+}
+
+#[allow(clippy::manual_div_ceil)] // msrv doesn't allow this
+fn align(value: usize, alignment: usize) -> usize {
+    (value + alignment - 1) / alignment * alignment
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    let mut x = a;
+    let mut y = b;
+    while y != 0 {
+        let t = x % y;
+        x = y;
+        y = t;
+    }
+    a / x * b
+}
@@ -2,13 +2,55 @@ use basis_sys as sys;
 use once_cell::sync::Lazy;
 use std::{
     convert::TryInto,
-    fmt,
-    mem::size_of,
+    mem::{size_of, MaybeUninit},
     num::NonZeroU32,
-    ptr,
-    sync::atomic::{AtomicBool, Ordering},
+    ops::{BitOr, BitOrAssign},
+    ptr, slice,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
+mod codebook;
+mod compressor;
+#[cfg(feature = "dds")]
+mod dds;
+mod error;
+mod export;
+mod graphics_api;
+#[cfg(feature = "image")]
+mod image_decoder;
+mod ktx2;
+mod layered;
+mod layout;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod video;
+#[cfg(feature = "wgpu")]
+mod wgpu_interop;
+
+pub use codebook::SharedCodebook;
+pub use compressor::{
+    CancellationToken, CompressedFile, CompressionPhase, CompressionProgress, CompressionStats, Compressor, Container,
+    LevelStats, MipFilter, MipmapOptions, SourceImage, UastcLevel, UastcRdo,
+};
+#[cfg(feature = "dds")]
+pub use ddsfile;
+pub use error::BasisError;
+#[cfg(feature = "image")]
+pub use image;
+#[cfg(feature = "image")]
+pub use image_decoder::BasisDecoder;
+pub use ktx2::{Ktx2ColorInfo, Ktx2ImageLevelInfo, Ktx2KeyValue, Ktx2Transcoder};
+pub use layered::{CubeFace, LayeredLevel};
+pub use layout::{StagingLayout, StagingLayoutBuilder, Subresource};
+#[cfg(feature = "rayon")]
+pub use parallel::TranscodedImageLevel;
+pub use video::{VideoDecoder, VideoFrame};
+#[cfg(feature = "wgpu")]
+pub use wgpu_types;
+
 static GLOBAL_STATE: Lazy<()> = Lazy::new(|| unsafe { sys::basisrs_init() });
 
 /// Initialize global state that needs to be initialized.
@@ -31,15 +73,25 @@ pub enum TextureType {
     Total,
 }
 impl TextureType {
-    fn from_internal(value: sys::basis_texture_type) -> Self {
+    fn from_internal(value: sys::basis_texture_type) -> Result<Self, BasisError> {
         match value {
-            sys::basis_texture_type_cBASISTexType2D => Self::D2,
-            sys::basis_texture_type_cBASISTexType2DArray => Self::D2Array,
-            sys::basis_texture_type_cBASISTexTypeCubemapArray => Self::CubemapArray,
-            sys::basis_texture_type_cBASISTexTypeVideoFrames => Self::VideoFrames,
-            sys::basis_texture_type_cBASISTexTypeVolume => Self::D3,
-            sys::basis_texture_type_cBASISTexTypeTotal => Self::Total,
-            _ => unreachable!("invalid internal texture type"),
+            sys::basis_texture_type_cBASISTexType2D => Ok(Self::D2),
+            sys::basis_texture_type_cBASISTexType2DArray => Ok(Self::D2Array),
+            sys::basis_texture_type_cBASISTexTypeCubemapArray => Ok(Self::CubemapArray),
+            sys::basis_texture_type_cBASISTexTypeVideoFrames => Ok(Self::VideoFrames),
+            sys::basis_texture_type_cBASISTexTypeVolume => Ok(Self::D3),
+            sys::basis_texture_type_cBASISTexTypeTotal => Ok(Self::Total),
+            _ => Err(BasisError::UnknownTextureType(value as u32)),
+        }
+    }
+    fn as_internal(self) -> sys::basis_texture_type {
+        match self {
+            Self::D2 => sys::basis_texture_type_cBASISTexType2D,
+            Self::D2Array => sys::basis_texture_type_cBASISTexType2DArray,
+            Self::CubemapArray => sys::basis_texture_type_cBASISTexTypeCubemapArray,
+            Self::VideoFrames => sys::basis_texture_type_cBASISTexTypeVideoFrames,
+            Self::D3 => sys::basis_texture_type_cBASISTexTypeVolume,
+            Self::Total => sys::basis_texture_type_cBASISTexTypeTotal,
         }
     }
 }
@@ -50,11 +102,11 @@ pub enum BasisTextureFormat {
     UAstc,
 }
 impl BasisTextureFormat {
-    fn from_internal(value: sys::basis_tex_format) -> Self {
+    fn from_internal(value: sys::basis_tex_format) -> Result<Self, BasisError> {
         match value {
-            sys::basis_tex_format_cETC1S => Self::Etc1s,
-            sys::basis_tex_format_cUASTC4x4 => Self::UAstc,
-            _ => unreachable!("invalid internal basis texture format"),
+            sys::basis_tex_format_cETC1S => Ok(Self::Etc1s),
+            sys::basis_tex_format_cUASTC4x4 => Ok(Self::UAstc),
+            _ => Err(BasisError::UnknownBasisFormat(value as u32)),
         }
     }
 
@@ -157,6 +209,224 @@ impl TargetTextureFormat {
     }
 }
 
+/// Low-level block formats the transcoder writes when transcoding individual slices.
+///
+/// Most [`TargetTextureFormat`]s are made out of one or two of these.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BlockFormat {
+    /// ETC1S RGB
+    Etc1,
+    /// Full ETC2 EAC RGBA8 block
+    Etc2Rgba,
+    /// DXT1 RGB
+    Bc1,
+    /// BC4 block followed by a four color BC1 block
+    Bc3,
+    /// DXT5A (alpha block only)
+    Bc4,
+    /// Two BC4 blocks
+    Bc5,
+    /// Opaque-only PVRTC1 4bpp
+    Pvrtc1Rgb,
+    /// PVRTC1 4bpp RGBA
+    Pvrtc1Rgba,
+    /// Full BC7 block, any mode
+    Bc7,
+    /// RGB BC7 mode 5 color, writes an opaque mode 5 block
+    Bc7M5Color,
+    /// Alpha portion of BC7 mode 5. [`Bc7M5Color`](Self::Bc7M5Color) must have been written to the output first.
+    Bc7M5Alpha,
+    /// Alpha block of ETC2 EAC, the first 8 bytes of an ETC2 EAC RGBA8 block
+    Etc2EacA8,
+    /// ASTC 4x4, either color-only or color and alpha
+    Astc4x4,
+    AtcRgb,
+    AtcRgbaInterpolatedAlpha,
+    /// Opaque-only, uses 8x4 pixel blocks
+    Fxt1Rgb,
+    Pvrtc2Rgb,
+    Pvrtc2Rgba,
+    Etc2EacR11,
+    Etc2EacRg11,
+    /// RGB components written to 32bpp pixels
+    Rgb32,
+    /// RGBA components written to 32bpp pixels
+    Rgba32,
+    /// Alpha component written to 32bpp pixels
+    A32,
+    Rgb565,
+    Bgr565,
+    Rgba4444Color,
+    Rgba4444Alpha,
+    Rgba4444ColorOpaque,
+    Rgba4444,
+}
+impl BlockFormat {
+    #[allow(clippy::match_like_matches_macro)] // msrv doesn't allow this
+    pub fn is_uncompressed(&self) -> bool {
+        match self {
+            Self::Rgb32
+            | Self::Rgba32
+            | Self::A32
+            | Self::Rgb565
+            | Self::Bgr565
+            | Self::Rgba4444Color
+            | Self::Rgba4444Alpha
+            | Self::Rgba4444ColorOpaque
+            | Self::Rgba4444 => true,
+            _ => false,
+        }
+    }
+
+    /// Size in bytes of a single block, or a single pixel for uncompressed formats.
+    pub fn bytes_per_block_or_pixel(&self) -> usize {
+        match self {
+            Self::Etc1
+            | Self::Bc1
+            | Self::Bc4
+            | Self::Pvrtc1Rgb
+            | Self::Pvrtc1Rgba
+            | Self::Etc2EacA8
+            | Self::AtcRgb
+            | Self::Pvrtc2Rgb
+            | Self::Pvrtc2Rgba
+            | Self::Etc2EacR11 => 8,
+            Self::Etc2Rgba
+            | Self::Bc3
+            | Self::Bc5
+            | Self::Bc7
+            | Self::Bc7M5Color
+            | Self::Bc7M5Alpha
+            | Self::Astc4x4
+            | Self::AtcRgbaInterpolatedAlpha
+            | Self::Fxt1Rgb
+            | Self::Etc2EacRg11 => 16,
+            Self::Rgb32 | Self::Rgba32 | Self::A32 => size_of::<u32>(),
+            Self::Rgb565
+            | Self::Bgr565
+            | Self::Rgba4444Color
+            | Self::Rgba4444Alpha
+            | Self::Rgba4444ColorOpaque
+            | Self::Rgba4444 => size_of::<u16>(),
+        }
+    }
+
+    fn as_internal(&self) -> sys::block_format {
+        match self {
+            Self::Etc1 => sys::block_format_cETC1,
+            Self::Etc2Rgba => sys::block_format_cETC2_RGBA,
+            Self::Bc1 => sys::block_format_cBC1,
+            Self::Bc3 => sys::block_format_cBC3,
+            Self::Bc4 => sys::block_format_cBC4,
+            Self::Bc5 => sys::block_format_cBC5,
+            Self::Pvrtc1Rgb => sys::block_format_cPVRTC1_4_RGB,
+            Self::Pvrtc1Rgba => sys::block_format_cPVRTC1_4_RGBA,
+            Self::Bc7 => sys::block_format_cBC7,
+            Self::Bc7M5Color => sys::block_format_cBC7_M5_COLOR,
+            Self::Bc7M5Alpha => sys::block_format_cBC7_M5_ALPHA,
+            Self::Etc2EacA8 => sys::block_format_cETC2_EAC_A8,
+            Self::Astc4x4 => sys::block_format_cASTC_4x4,
+            Self::AtcRgb => sys::block_format_cATC_RGB,
+            Self::AtcRgbaInterpolatedAlpha => sys::block_format_cATC_RGBA_INTERPOLATED_ALPHA,
+            Self::Fxt1Rgb => sys::block_format_cFXT1_RGB,
+            Self::Pvrtc2Rgb => sys::block_format_cPVRTC2_4_RGB,
+            Self::Pvrtc2Rgba => sys::block_format_cPVRTC2_4_RGBA,
+            Self::Etc2EacR11 => sys::block_format_cETC2_EAC_R11,
+            Self::Etc2EacRg11 => sys::block_format_cETC2_EAC_RG11,
+            Self::Rgb32 => sys::block_format_cRGB32,
+            Self::Rgba32 => sys::block_format_cRGBA32,
+            Self::A32 => sys::block_format_cA32,
+            Self::Rgb565 => sys::block_format_cRGB565,
+            Self::Bgr565 => sys::block_format_cBGR565,
+            Self::Rgba4444Color => sys::block_format_cRGBA4444_COLOR,
+            Self::Rgba4444Alpha => sys::block_format_cRGBA4444_ALPHA,
+            Self::Rgba4444ColorOpaque => sys::block_format_cRGBA4444_COLOR_OPAQUE,
+            Self::Rgba4444 => sys::block_format_cRGBA4444,
+        }
+    }
+}
+
+/// Optional parameters for [`PreparedBasisFile::transcode_slice`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct SliceTranscodeOptions {
+    /// Bytes between the start of two consecutive output blocks (or pixels).
+    ///
+    /// Defaults to [`BlockFormat::bytes_per_block_or_pixel`]. A larger stride allows interleaving
+    /// several block formats, like writing [`BlockFormat::Etc2EacA8`] into the first half of 16 byte blocks.
+    pub output_block_stride: Option<u32>,
+    pub decode_flags: DecodeFlags,
+    /// Blocks (or pixels) per output row. 0 means the slice's width. Ignored for PVRTC1.
+    pub row_pitch: u32,
+    /// Output rows in pixels, only used by uncompressed formats. 0 means the slice's height.
+    pub rows: u32,
+    /// Source channel used for single channel outputs like [`BlockFormat::Bc4`], or the first channel
+    /// of two channel outputs. Channels 0 to 3 are red, green, blue and alpha. `None` uses the
+    /// transcoder's default.
+    pub channel0: Option<u8>,
+    /// Source channel used for the second channel of two channel outputs like [`BlockFormat::Bc5`].
+    /// `None` uses the transcoder's default.
+    pub channel1: Option<u8>,
+}
+
+/// Set of options that change how the transcoder decodes a level.
+///
+/// Flags can be combined with `|`.
+///
+/// PVRTC1 output always uses wrap addressing, so there is no flag for it. The bundled basis_universal
+/// has no option to turn off ETC1S chroma filtering either.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DecodeFlags(u32);
+impl DecodeFlags {
+    /// When decoding to an opaque format, decode the alpha slice instead of the color slice.
+    ///
+    /// Allows a texture with alpha to be split into two opaque textures, one for color and one for alpha.
+    pub const TRANSCODE_ALPHA_DATA_TO_OPAQUE_FORMATS: Self =
+        Self(sys::basisu_decode_flags_cDecodeFlagsTranscodeAlphaDataToOpaqueFormats);
+    /// BC1: never emit three color blocks.
+    pub const BC1_FORBID_THREE_COLOR_BLOCKS: Self =
+        Self(sys::basisu_decode_flags_cDecodeFlagsBC1ForbidThreeColorBlocks);
+    /// UASTC: use the slower, higher quality transcoding paths to BC7, ETC1/2, BC1/3 and PVRTC1.
+    pub const HIGH_QUALITY: Self = Self(sys::basisu_decode_flags_cDecodeFlagsHighQuality);
+
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+}
+
+impl BitOr for DecodeFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for DecodeFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct UserData {
     pub word0: u32,
@@ -316,10 +586,10 @@ pub struct FileInfo {
     pub has_alpha_slices: bool,
 }
 impl FileInfo {
-    fn from_internal(value: sys::basisu_file_info) -> Self {
+    fn from_internal(value: sys::basisu_file_info) -> Result<Self, BasisError> {
         let slice_info = unsafe { sys::basisrs_file_info_get_slice_info(&value as *const _) };
         let mipmap_levels = unsafe { sys::basisrs_file_info_get_mipmap_levels(&value as *const _) };
-        Self {
+        Ok(Self {
             version: value.m_version,
             total_header_size: value.m_total_header_size,
             total_selectors: value.m_total_selectors,
@@ -328,7 +598,7 @@ impl FileInfo {
             endpoint_codebook_size: value.m_endpoint_codebook_size,
             tables_size: value.m_tables_size,
             slices_size: value.m_slices_size,
-            tex_type: TextureType::from_internal(value.m_tex_type),
+            tex_type: TextureType::from_internal(value.m_tex_type)?,
             us_per_frame: value.m_us_per_frame,
             slice_info: read_slice_info(slice_info),
             total_images: value.m_total_images,
@@ -337,17 +607,18 @@ impl FileInfo {
                 word0: value.m_userdata0,
                 word1: value.m_userdata1,
             },
-            basis_format: BasisTextureFormat::from_internal(value.m_tex_format),
+            basis_format: BasisTextureFormat::from_internal(value.m_tex_format)?,
             y_flipped: value.m_y_flipped,
             etc1s: value.m_etc1s,
             has_alpha_slices: value.m_has_alpha_slices,
-        }
+        })
     }
 }
 
 pub struct Transcoder {
     inner: *mut sys::basisu_transcoder,
     recording: AtomicBool,
+    shared_codebook: Option<Arc<SharedCodebook>>,
 }
 impl Transcoder {
     pub fn new() -> Self {
@@ -356,31 +627,65 @@ impl Transcoder {
         Self {
             inner,
             recording: AtomicBool::new(false),
+            shared_codebook: None,
         }
     }
 
-    pub fn validate_file_checksums(&self, file: &[u8], full_validation: bool) -> bool {
-        let length = validate_slice_length(file);
+    /// Creates a transcoder for files encoded against `shared_codebook`. Files with their own codebooks
+    /// can still be transcoded.
+    pub fn with_shared_codebook(shared_codebook: Arc<SharedCodebook>) -> Self {
+        let mut transcoder = Self::new();
+
+        unsafe { sys::basisrs_set_global_codebooks(transcoder.inner, shared_codebook.as_internal()) };
+        transcoder.shared_codebook = Some(shared_codebook);
+
+        transcoder
+    }
 
-        unsafe { sys::basisrs_validate_file_checksums(self.inner, file.as_ptr() as _, length, full_validation) }
+    /// The codebook given to [`with_shared_codebook`](Self::with_shared_codebook).
+    pub fn shared_codebook(&self) -> Option<&Arc<SharedCodebook>> {
+        self.shared_codebook.as_ref()
     }
 
-    pub fn validate_header(&self, file: &[u8]) -> bool {
-        let length = validate_slice_length(file);
+    pub fn validate_file_checksums(&self, file: &[u8], full_validation: bool) -> Result<(), BasisError> {
+        self.validate_header(file)?;
 
-        unsafe { sys::basisrs_validate_header(self.inner, file.as_ptr() as _, length) }
+        let length = validate_slice_length(file)?;
+
+        let res =
+            unsafe { sys::basisrs_validate_file_checksums(self.inner, file.as_ptr() as _, length, full_validation) };
+
+        if res {
+            Ok(())
+        } else {
+            Err(BasisError::ChecksumMismatch)
+        }
     }
 
-    pub fn get_texture_type(&self, file: &[u8]) -> TextureType {
-        let length = validate_slice_length(file);
+    pub fn validate_header(&self, file: &[u8]) -> Result<(), BasisError> {
+        let length = validate_slice_length(file)?;
+
+        let res = unsafe { sys::basisrs_validate_header(self.inner, file.as_ptr() as _, length) };
+
+        if res {
+            Ok(())
+        } else {
+            Err(BasisError::InvalidHeader)
+        }
+    }
+
+    pub fn get_texture_type(&self, file: &[u8]) -> Result<TextureType, BasisError> {
+        self.validate_header(file)?;
+
+        let length = validate_slice_length(file)?;
 
         let res = unsafe { sys::basisrs_get_texture_type(self.inner, file.as_ptr() as _, length) };
 
         TextureType::from_internal(res)
     }
 
-    pub fn get_userdata(&self, file: &[u8]) -> Option<UserData> {
-        let length = validate_slice_length(file);
+    pub fn get_userdata(&self, file: &[u8]) -> Result<UserData, BasisError> {
+        let length = validate_slice_length(file)?;
 
         let mut data = UserData { word0: 0, word1: 0 };
 
@@ -395,32 +700,39 @@ impl Transcoder {
         };
 
         if res {
-            Some(data)
+            Ok(data)
         } else {
-            None
+            Err(BasisError::InvalidHeader)
         }
     }
 
-    pub fn get_total_images(&self, file: &[u8]) -> NonZeroU32 {
-        let length = validate_slice_length(file);
+    pub fn get_total_images(&self, file: &[u8]) -> Result<NonZeroU32, BasisError> {
+        let length = validate_slice_length(file)?;
 
         let res = unsafe { sys::basisrs_get_total_images(self.inner, file.as_ptr() as _, length) };
 
-        NonZeroU32::new(res).expect("documentation asserts that get_total_images will return non-zero number")
+        // The transcoder only returns 0 when the header is invalid.
+        NonZeroU32::new(res).ok_or(BasisError::InvalidHeader)
     }
 
-    pub fn get_tex_format(&self, file: &[u8]) -> BasisTextureFormat {
-        let length = validate_slice_length(file);
+    pub fn get_tex_format(&self, file: &[u8]) -> Result<BasisTextureFormat, BasisError> {
+        self.validate_header(file)?;
+
+        let length = validate_slice_length(file)?;
 
         let res = unsafe { sys::basisrs_get_tex_format(self.inner, file.as_ptr() as _, length) };
 
         BasisTextureFormat::from_internal(res)
     }
 
-    pub fn get_total_image_levels(&self, file: &[u8], image_index: u32) -> u32 {
-        let length = validate_slice_length(file);
+    pub fn get_total_image_levels(&self, file: &[u8], image_index: u32) -> Result<u32, BasisError> {
+        self.check_image(file, image_index)?;
+
+        let length = validate_slice_length(file)?;
 
-        unsafe { sys::basisrs_get_total_image_levels(self.inner, file.as_ptr() as _, length, image_index) }
+        let res = unsafe { sys::basisrs_get_total_image_levels(self.inner, file.as_ptr() as _, length, image_index) };
+
+        Ok(res)
     }
 
     pub fn get_basic_image_level_info(
@@ -428,8 +740,10 @@ impl Transcoder {
         file: &[u8],
         image_index: u32,
         level_index: u32,
-    ) -> Option<BasicImageLevelInfo> {
-        let length = validate_slice_length(file);
+    ) -> Result<BasicImageLevelInfo, BasisError> {
+        self.check_level(file, image_index, level_index)?;
+
+        let length = validate_slice_length(file)?;
 
         let mut data = BasicImageLevelInfo {
             orig_width: 0,
@@ -451,14 +765,16 @@ impl Transcoder {
         };
 
         if res {
-            Some(data)
+            Ok(data)
         } else {
-            None
+            Err(BasisError::InvalidFile)
         }
     }
 
-    pub fn get_image_info(&self, file: &[u8], image_index: u32) -> Option<ImageInfo> {
-        let length = validate_slice_length(file);
+    pub fn get_image_info(&self, file: &[u8], image_index: u32) -> Result<ImageInfo, BasisError> {
+        self.check_image(file, image_index)?;
+
+        let length = validate_slice_length(file)?;
 
         let mut data = sys::basisu_image_info {
             m_image_index: 0,
@@ -480,14 +796,21 @@ impl Transcoder {
         };
 
         if res {
-            Some(ImageInfo::from_internal(data))
+            Ok(ImageInfo::from_internal(data))
         } else {
-            None
+            Err(BasisError::InvalidFile)
         }
     }
 
-    pub fn get_image_level_info(&self, file: &[u8], image_index: u32, level_index: u32) -> Option<ImageLevelInfo> {
-        let length = validate_slice_length(file);
+    pub fn get_image_level_info(
+        &self,
+        file: &[u8],
+        image_index: u32,
+        level_index: u32,
+    ) -> Result<ImageLevelInfo, BasisError> {
+        self.check_level(file, image_index, level_index)?;
+
+        let length = validate_slice_length(file)?;
 
         let mut data = sys::basisu_image_level_info {
             m_image_index: 0,
@@ -516,14 +839,16 @@ impl Transcoder {
         };
 
         if res {
-            Some(ImageLevelInfo::from_internal(data))
+            Ok(ImageLevelInfo::from_internal(data))
         } else {
-            None
+            Err(BasisError::InvalidFile)
         }
     }
 
-    pub fn get_file_info(&self, file: &[u8]) -> Option<FileInfo> {
-        let length = validate_slice_length(file);
+    pub fn get_file_info(&self, file: &[u8]) -> Result<FileInfo, BasisError> {
+        self.validate_header(file)?;
+
+        let length = validate_slice_length(file)?;
 
         let mut data = sys::basisu_file_info {
             m_version: 0,
@@ -550,28 +875,69 @@ impl Transcoder {
         let res = unsafe { sys::basisrs_get_file_info(self.inner, file.as_ptr() as _, length, &mut data as *mut _) };
 
         if res {
-            Some(FileInfo::from_internal(data))
+            FileInfo::from_internal(data)
         } else {
-            None
+            Err(BasisError::InvalidFile)
         }
     }
 
-    pub fn prepare_transcoding<'a>(&'a self, file: &'a [u8]) -> Option<PreparedBasisFile<'a>> {
+    /// Prepares the transcoder to transcode `file`.
+    ///
+    /// Only one file can be prepared with a transcoder at a time. Preparing another file while the
+    /// returned [`PreparedBasisFile`] is alive returns [`BasisError::TranscoderBusy`].
+    pub fn prepare_transcoding<'a>(&'a self, file: &'a [u8]) -> Result<PreparedBasisFile<'a>, BasisError> {
         init();
 
+        let file_info = self.get_file_info(file)?;
+
         let locked = self.recording.swap(true, Ordering::Acquire);
 
         if locked {
-            return None;
+            return Err(BasisError::TranscoderBusy);
         }
 
-        let length = validate_slice_length(file);
+        let length = validate_slice_length(file)?;
 
         let res = unsafe { sys::basisrs_start_transcoding(self.inner, file.as_ptr() as _, length) };
 
-        assert!(res, "transcoding started while transcoding still in progress");
+        if !res {
+            self.recording.store(false, Ordering::Release);
+            return Err(BasisError::InvalidFile);
+        }
 
-        Some(PreparedBasisFile { transcoder: self, file })
+        Ok(PreparedBasisFile {
+            transcoder: self,
+            file,
+            basis_format: file_info.basis_format,
+            file_info,
+        })
+    }
+
+    fn check_image(&self, file: &[u8], image_index: u32) -> Result<(), BasisError> {
+        let total_images = self.get_total_images(file)?.get();
+
+        if image_index < total_images {
+            Ok(())
+        } else {
+            Err(BasisError::ImageOutOfRange {
+                image_index,
+                total_images,
+            })
+        }
+    }
+
+    fn check_level(&self, file: &[u8], image_index: u32, level_index: u32) -> Result<(), BasisError> {
+        let total_levels = self.get_total_image_levels(file, image_index)?;
+
+        if level_index < total_levels {
+            Ok(())
+        } else {
+            Err(BasisError::LevelOutOfRange {
+                image_index,
+                level_index,
+                total_levels,
+            })
+        }
     }
 }
 
@@ -590,72 +956,405 @@ impl Default for Transcoder {
     }
 }
 
+/// Scratch state used while transcoding.
+///
+/// Every thread transcoding from the same [`PreparedBasisFile`] needs its own state. ETC1S video
+/// also keeps the previous frame in here, so P-frames must be decoded with the same state as the
+/// frames before them.
+pub struct TranscoderState {
+    inner: *mut sys::basisu_transcoder_state,
+}
+impl TranscoderState {
+    pub fn new() -> Self {
+        let inner = unsafe { sys::basisrs_create_transcoder_state() };
+
+        Self { inner }
+    }
+}
+
+impl Drop for TranscoderState {
+    fn drop(&mut self) {
+        unsafe { sys::basisrs_destroy_transcoder_state(self.inner) }
+    }
+}
+
+unsafe impl Send for TranscoderState {}
+
+impl Default for TranscoderState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct PreparedBasisFile<'a> {
     transcoder: &'a Transcoder,
     file: &'a [u8],
+    basis_format: BasisTextureFormat,
+    /// Parsed once when the file is prepared, so transcoding doesn't parse the header and every slice
+    /// descriptor again.
+    file_info: FileInfo,
 }
 impl<'a> PreparedBasisFile<'a> {
+    /// Number of bytes needed to hold the given image level transcoded to `format`.
+    pub fn transcoded_size(
+        &self,
+        image_index: u32,
+        level_index: u32,
+        format: TargetTextureFormat,
+    ) -> Result<usize, BasisError> {
+        let level_info = self.level_info(image_index, level_index)?;
+
+        LevelShape::new(&level_info, format).total_bytes(0)
+    }
+
     pub fn transcode_image_level(
         &mut self,
         image_index: u32,
         level_index: u32,
         format: TargetTextureFormat,
-    ) -> Result<Vec<u8>, TranscodeError> {
-        let file_info = self.transcoder.get_file_info(&self.file).unwrap().basis_format;
+        decode_flags: DecodeFlags,
+    ) -> Result<Vec<u8>, BasisError> {
+        let mut result = vec![0; self.transcoded_size(image_index, level_index, format)?];
 
-        match (file_info, format) {
-            (BasisTextureFormat::UAstc, TargetTextureFormat::AtcRgb)
-            | (BasisTextureFormat::UAstc, TargetTextureFormat::AtcRgbA)
-            | (BasisTextureFormat::UAstc, TargetTextureFormat::Fxt1Rgb)
-            | (BasisTextureFormat::UAstc, TargetTextureFormat::Pvrtc2Rgb)
-            | (BasisTextureFormat::UAstc, TargetTextureFormat::Pvrtc2Rgba) => {
-                return Err(TranscodeError::UnsupportedFormatFromUastc(format))
-            }
-            (_, TargetTextureFormat::Rgba4444) => return Err(TranscodeError::UnsupportedFormatBug),
-            _ => {}
+        self.transcode_image_level_into(image_index, level_index, format, decode_flags, &mut result)?;
+
+        Ok(result)
+    }
+
+    /// Transcodes the given image level into `output` instead of allocating a new buffer.
+    ///
+    /// `output` must be at least [`transcoded_size`](Self::transcoded_size) bytes long.
+    /// Returns the number of bytes written to the front of `output`.
+    pub fn transcode_image_level_into(
+        &mut self,
+        image_index: u32,
+        level_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+        output: &mut [u8],
+    ) -> Result<usize, BasisError> {
+        unsafe {
+            self.transcode_image_level_raw(
+                ptr::null_mut(),
+                image_index,
+                level_index,
+                format,
+                decode_flags,
+                0,
+                output.as_mut_ptr(),
+                output.len(),
+            )
         }
+    }
 
-        let level_info = self
-            .transcoder
-            .get_basic_image_level_info(&self.file, image_index, level_index)
-            .unwrap();
+    /// Same as [`transcode_image_level_into`](Self::transcode_image_level_into), but allows the output to
+    /// be uninitialized memory.
+    ///
+    /// On success, the first `n` bytes of `output` are initialized, where `n` is the returned length.
+    pub fn transcode_image_level_into_uninit(
+        &mut self,
+        image_index: u32,
+        level_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+        output: &mut [MaybeUninit<u8>],
+    ) -> Result<usize, BasisError> {
+        unsafe {
+            self.transcode_image_level_raw(
+                ptr::null_mut(),
+                image_index,
+                level_index,
+                format,
+                decode_flags,
+                0,
+                output.as_mut_ptr() as *mut u8,
+                output.len(),
+            )
+        }
+    }
 
-        let total_size = level_info.total_blocks as usize * format.block_size();
+    /// Same as [`transcode_image_level`](Self::transcode_image_level), but uses `state` instead of the
+    /// transcoder's internal state.
+    ///
+    /// As this only needs `&self`, multiple threads can transcode different levels or images of the same
+    /// file at once, as long as each uses its own state.
+    pub fn transcode_image_level_with_state(
+        &self,
+        state: &mut TranscoderState,
+        image_index: u32,
+        level_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+    ) -> Result<Vec<u8>, BasisError> {
+        let mut result = vec![0; self.transcoded_size(image_index, level_index, format)?];
+
+        self.transcode_image_level_into_with_state(state, image_index, level_index, format, decode_flags, &mut result)?;
 
-        let mut result: Vec<u8> = Vec::new();
-        result.resize(total_size, 0);
+        Ok(result)
+    }
+
+    /// Same as [`transcode_image_level_into`](Self::transcode_image_level_into), but uses `state` instead
+    /// of the transcoder's internal state.
+    pub fn transcode_image_level_into_with_state(
+        &self,
+        state: &mut TranscoderState,
+        image_index: u32,
+        level_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+        output: &mut [u8],
+    ) -> Result<usize, BasisError> {
+        unsafe {
+            self.transcode_image_level_raw(
+                state.inner,
+                image_index,
+                level_index,
+                format,
+                decode_flags,
+                0,
+                output.as_mut_ptr(),
+                output.len(),
+            )
+        }
+    }
 
-        let output_blocks_buf_size = if format.is_uncompressed() {
-            level_info.orig_width * level_info.orig_height
+    /// `row_pitch` is in blocks, or pixels for uncompressed formats. 0 means tightly packed.
+    ///
+    /// # Safety
+    ///
+    /// `output` must be valid for writes of `output_len` bytes. If `state` is null, the transcoder's
+    /// internal state is used, so the caller must have exclusive access to `self`.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn transcode_image_level_raw(
+        &self,
+        state: *mut sys::basisu_transcoder_state,
+        image_index: u32,
+        level_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+        row_pitch: u32,
+        output: *mut u8,
+        output_len: usize,
+    ) -> Result<usize, BasisError> {
+        if !self.basis_format.supports_texture_format(format) {
+            return Err(BasisError::UnsupportedTarget {
+                basis_format: self.basis_format,
+                target: format,
+            });
+        }
+
+        let level_info = self.level_info(image_index, level_index)?;
+
+        let shape = LevelShape::new(&level_info, format);
+        let total_units = shape.total_units(row_pitch)?;
+        let required = shape.total_bytes(row_pitch)?;
+
+        if output_len < required {
+            return Err(BasisError::BufferTooSmall {
+                required,
+                provided: output_len,
+            });
+        }
+
+        // The row count is only used for uncompressed formats, and only matters if the rows are padded.
+        let rows_in_pixels = if row_pitch != 0 && format.is_uncompressed() {
+            shape.rows
         } else {
-            level_info.total_blocks
+            0
         };
 
         let texture_format = format.as_internal();
 
+        let res = sys::basisrs_transcode_image_level(
+            self.transcoder.inner,
+            self.file.as_ptr() as _,
+            self.file.len() as _,
+            image_index,
+            level_index,
+            output as *mut _,
+            total_units,
+            texture_format,
+            decode_flags.bits(),
+            row_pitch,
+            state,
+            rows_in_pixels,
+        );
+
+        if res {
+            Ok(required)
+        } else {
+            Err(BasisError::TranscodeFailed)
+        }
+    }
+
+    /// Finds the slice holding the color (or alpha) data of an image level.
+    ///
+    /// Returns `None` if the level has no such slice, like the alpha slice of an opaque file.
+    pub fn find_slice(&self, image_index: u32, level_index: u32, alpha_data: bool) -> Result<Option<u32>, BasisError> {
+        self.check_level(image_index, level_index)?;
+
         let res = unsafe {
-            sys::basisrs_transcode_image_level(
+            sys::basisrs_find_slice(
                 self.transcoder.inner,
                 self.file.as_ptr() as _,
                 self.file.len() as _,
                 image_index,
                 level_index,
-                result.as_mut_ptr() as *mut _,
-                output_blocks_buf_size,
-                texture_format,
-                0,               // decode flags
-                0,               // row pitch; deduced from output
+                alpha_data,
+            )
+        };
+
+        Ok(res.try_into().ok())
+    }
+
+    /// Low-level transcode of a single slice to a single block format.
+    ///
+    /// Some target formats need multiple slices to be transcoded into the same output, so most users will
+    /// want [`transcode_image_level`](Self::transcode_image_level) instead.
+    ///
+    /// `alpha_blocks` is scratch space some formats use to hold the alpha slice's data, and must be at least
+    /// as large as `output` if given. Returns the number of bytes the output spans.
+    pub fn transcode_slice(
+        &mut self,
+        slice_index: u32,
+        format: BlockFormat,
+        options: &SliceTranscodeOptions,
+        output: &mut [u8],
+        alpha_blocks: Option<&mut [u8]>,
+    ) -> Result<usize, BasisError> {
+        if let Some(channel) = options
+            .channel0
+            .into_iter()
+            .chain(options.channel1)
+            .find(|&channel| channel > 3)
+        {
+            return Err(BasisError::InvalidChannel(channel));
+        }
+
+        let slices = &self.file_info.slice_info;
+        let slice_info =
+            slices
+                .iter()
+                .find(|slice| slice.slice_index == slice_index)
+                .ok_or(BasisError::SliceOutOfRange {
+                    slice_index,
+                    total_slices: slices.len() as u32,
+                })?;
+
+        let (row_units, rows) = if format.is_uncompressed() {
+            (slice_info.orig_width, slice_info.orig_height)
+        } else if format == BlockFormat::Fxt1Rgb {
+            (div_round_up(slice_info.orig_width, 8), slice_info.num_blocks_y)
+        } else {
+            (slice_info.num_blocks_x, slice_info.num_blocks_y)
+        };
+        let row_pitch = options.row_pitch.max(row_units);
+        let rows = if format.is_uncompressed() {
+            options.rows.max(rows)
+        } else {
+            rows
+        };
+        let stride = options
+            .output_block_stride
+            .unwrap_or(format.bytes_per_block_or_pixel() as u32)
+            .max(format.bytes_per_block_or_pixel() as u32);
+
+        let total_units = row_pitch.checked_mul(rows).ok_or(BasisError::OutputTooLarge)?;
+        let required = (total_units as usize)
+            .checked_mul(stride as usize)
+            .ok_or(BasisError::OutputTooLarge)?;
+
+        if output.len() < required {
+            return Err(BasisError::BufferTooSmall {
+                required,
+                provided: output.len(),
+            });
+        }
+
+        let alpha_blocks = match alpha_blocks {
+            Some(alpha_blocks) if alpha_blocks.len() < required => {
+                return Err(BasisError::BufferTooSmall {
+                    required,
+                    provided: alpha_blocks.len(),
+                })
+            }
+            Some(alpha_blocks) => alpha_blocks.as_mut_ptr(),
+            None => ptr::null_mut(),
+        };
+
+        let res = unsafe {
+            sys::basisrs_transcode_slice(
+                self.transcoder.inner,
+                self.file.as_ptr() as _,
+                self.file.len() as _,
+                slice_index,
+                output.as_mut_ptr() as *mut _,
+                total_units,
+                format.as_internal(),
+                stride,
+                options.decode_flags.bits(),
+                options.row_pitch,
                 ptr::null_mut(), // transcoder state
-                0,               // row count; deduced from output
+                alpha_blocks as *mut _,
+                options.rows,
+                options.channel0.map_or(-1, |c| c as _),
+                options.channel1.map_or(-1, |c| c as _),
             )
         };
 
         if res {
-            Ok(result)
+            Ok(required)
         } else {
-            Err(TranscodeError::OtherError)
+            Err(BasisError::TranscodeFailed)
+        }
+    }
+
+    fn check_image(&self, image_index: u32) -> Result<(), BasisError> {
+        let total_images = self.file_info.total_images;
+
+        if image_index < total_images {
+            Ok(())
+        } else {
+            Err(BasisError::ImageOutOfRange {
+                image_index,
+                total_images,
+            })
         }
     }
+
+    fn check_level(&self, image_index: u32, level_index: u32) -> Result<(), BasisError> {
+        self.check_image(image_index)?;
+
+        let total_levels = self.file_info.image_mipmap_levels[image_index as usize];
+
+        if level_index < total_levels {
+            Ok(())
+        } else {
+            Err(BasisError::LevelOutOfRange {
+                image_index,
+                level_index,
+                total_levels,
+            })
+        }
+    }
+
+    /// Same as [`Transcoder::get_basic_image_level_info`], read from the level's first slice.
+    fn level_info(&self, image_index: u32, level_index: u32) -> Result<BasicImageLevelInfo, BasisError> {
+        self.check_level(image_index, level_index)?;
+
+        let slice = self
+            .file_info
+            .slice_info
+            .iter()
+            .find(|slice| slice.image_index == image_index && slice.level_index == level_index)
+            .ok_or(BasisError::InvalidFile)?;
+
+        Ok(BasicImageLevelInfo {
+            orig_width: slice.orig_width,
+            orig_height: slice.orig_height,
+            total_blocks: slice.total_blocks,
+        })
+    }
 }
 impl<'a> Drop for PreparedBasisFile<'a> {
     fn drop(&mut self) {
@@ -667,31 +1366,78 @@ impl<'a> Drop for PreparedBasisFile<'a> {
     }
 }
 
-#[derive(Debug)]
-pub enum TranscodeError {
-    UnsupportedFormatFromUastc(TargetTextureFormat),
-    UnsupportedFormatBug,
-    OtherError,
+/// Shape of a single transcoded image level, in the units the transcoder works in.
+#[derive(Debug, Copy, Clone)]
+struct LevelShape {
+    /// Blocks per row, or pixels per row for uncompressed formats.
+    row_units: u32,
+    /// Block rows, or pixel rows for uncompressed formats.
+    rows: u32,
+    /// Bytes per block, or bytes per pixel for uncompressed formats.
+    bytes_per_unit: usize,
 }
+impl LevelShape {
+    fn new(level_info: &BasicImageLevelInfo, format: TargetTextureFormat) -> Self {
+        let (row_units, rows) = if format.is_uncompressed() {
+            (level_info.orig_width, level_info.orig_height)
+        } else if format == TargetTextureFormat::Fxt1Rgb {
+            // FXT1 blocks are 8x4 pixels, so there are only half as many of them horizontally.
+            (
+                div_round_up(level_info.orig_width, 8),
+                div_round_up(level_info.orig_height, 4),
+            )
+        } else {
+            (
+                div_round_up(level_info.orig_width, 4),
+                div_round_up(level_info.orig_height, 4),
+            )
+        };
 
-impl fmt::Display for TranscodeError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            TranscodeError::UnsupportedFormatFromUastc(format) => {
-                write!(f, "Format {:?} cannot be converted from uastc basis format", format)
-            }
-            TranscodeError::UnsupportedFormatBug => write!(
-                f,
-                "Format {:?} cannot be written to because of a bug",
-                TargetTextureFormat::Rgba4444
-            ),
-            TranscodeError::OtherError => write!(f, "Another error has occurred. If in debug mode, check stderr"),
+        let bytes_per_unit = if format.is_uncompressed() {
+            format.block_size() / 16
+        } else {
+            format.block_size()
+        };
+
+        Self {
+            row_units,
+            rows,
+            bytes_per_unit,
         }
     }
+
+    /// What the transcoder calls `output_blocks_buf_size_in_blocks_or_pixels`, for a row pitch
+    /// given in blocks or pixels. A row pitch of 0 means tightly packed.
+    fn total_units(&self, row_pitch: u32) -> Result<u32, BasisError> {
+        row_pitch
+            .max(self.row_units)
+            .checked_mul(self.rows)
+            .ok_or(BasisError::OutputTooLarge)
+    }
+
+    fn total_bytes(&self, row_pitch: u32) -> Result<usize, BasisError> {
+        (self.total_units(row_pitch)? as usize)
+            .checked_mul(self.bytes_per_unit)
+            .ok_or(BasisError::OutputTooLarge)
+    }
+}
+
+#[allow(clippy::manual_div_ceil)] // msrv doesn't allow this
+fn div_round_up(value: u32, divisor: u32) -> u32 {
+    (value + divisor - 1) / divisor
 }
 
-impl std::error::Error for TranscodeError {}
+/// # Safety
+///
+/// `value` must point to `value.size` initialized bytes that outlive `'t`.
+unsafe fn read_vector_u8<'t>(value: sys::basisrs_vector_u8) -> &'t [u8] {
+    if value.size == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(value.values, value.size as _)
+    }
+}
 
-fn validate_slice_length<T>(slice: &[T]) -> u32 {
-    slice.len().try_into().expect("Slice is longer than u32::MAX")
+fn validate_slice_length<T>(slice: &[T]) -> Result<u32, BasisError> {
+    slice.len().try_into().map_err(|_| BasisError::FileTooLarge)
 }
@@ -0,0 +1,43 @@
+use crate::{BasisError, DecodeFlags, PreparedBasisFile, TargetTextureFormat, TranscoderState};
+use rayon::prelude::*;
+
+/// A single transcoded image level, as returned by [`PreparedBasisFile::transcode_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscodedImageLevel {
+    pub image_index: u32,
+    pub level_index: u32,
+    pub data: Vec<u8>,
+}
+
+impl<'a> PreparedBasisFile<'a> {
+    /// Transcodes every level of every image on the rayon thread pool.
+    ///
+    /// Results are ordered by image, then by level, regardless of the order they finished in.
+    pub fn transcode_all(
+        &self,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+    ) -> Result<Vec<TranscodedImageLevel>, BasisError> {
+        let levels: Vec<(u32, u32)> = self
+            .file_info
+            .image_mipmap_levels
+            .iter()
+            .enumerate()
+            .flat_map(|(image_index, &levels)| (0..levels).map(move |level_index| (image_index as u32, level_index)))
+            .collect();
+
+        levels
+            .into_par_iter()
+            .map_init(TranscoderState::new, |state, (image_index, level_index)| {
+                let data =
+                    self.transcode_image_level_with_state(state, image_index, level_index, format, decode_flags)?;
+
+                Ok(TranscodedImageLevel {
+                    image_index,
+                    level_index,
+                    data,
+                })
+            })
+            .collect()
+    }
+}
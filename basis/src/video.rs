@@ -0,0 +1,253 @@
+use crate::{
+    BasisError, BasisTextureFormat, DecodeFlags, PreparedBasisFile, TargetTextureFormat, TextureType, TranscoderState,
+};
+use std::time::Duration;
+
+/// A single decoded frame of a video.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoFrame {
+    pub frame_index: u32,
+    /// When the frame should be presented, relative to the start of the video.
+    pub timestamp: Duration,
+    /// Whether the file flags the frame as an I-frame. Only ETC1S videos have I-frames.
+    pub iframe: bool,
+    /// Whether the frame can be decoded without decoding any previous frame, like I-frames and every frame
+    /// of a UASTC video.
+    pub independent: bool,
+    pub data: Vec<u8>,
+}
+
+/// Decodes the frames of a [`TextureType::VideoFrames`] file.
+///
+/// ETC1S P-frames are stored as changes to the previous frame, so the decoder keeps its own
+/// [`TranscoderState`] and refuses to decode a P-frame unless the frame before it was just decoded.
+/// UASTC videos have no I-frames or P-frames, so any of their frames can be decoded.
+///
+/// Iterating the decoder yields every remaining frame in order.
+pub struct VideoDecoder<'p, 'a> {
+    prepared: &'p PreparedBasisFile<'a>,
+    state: TranscoderState,
+    level_index: u32,
+    format: TargetTextureFormat,
+    decode_flags: DecodeFlags,
+    us_per_frame: u32,
+    iframes: Vec<bool>,
+    /// Set for UASTC videos, whose frames don't depend on each other.
+    all_independent: bool,
+    next_frame: u32,
+}
+impl<'p, 'a> VideoDecoder<'p, 'a> {
+    /// Creates a decoder for the given mip level of every frame.
+    ///
+    /// Returns [`BasisError::InvalidFile`] for ETC1S videos that don't start with an I-frame, as their first
+    /// frame can't be decoded.
+    pub fn new(
+        prepared: &'p PreparedBasisFile<'a>,
+        level_index: u32,
+        format: TargetTextureFormat,
+        decode_flags: DecodeFlags,
+    ) -> Result<Self, BasisError> {
+        let file_info = &prepared.file_info;
+
+        if file_info.tex_type != TextureType::VideoFrames {
+            return Err(BasisError::NotVideo(file_info.tex_type));
+        }
+
+        // Like basis_universal, take each frame's flag from the first slice of its first level.
+        let mut iframes = vec![None; file_info.total_images as usize];
+        for slice in file_info.slice_info.iter().filter(|slice| slice.level_index == 0) {
+            if let Some(iframe) = iframes.get_mut(slice.image_index as usize) {
+                iframe.get_or_insert(slice.iframe_flag);
+            }
+        }
+        let iframes: Vec<bool> = iframes.into_iter().map(|iframe| iframe.unwrap_or(false)).collect();
+
+        // Only ETC1S P-frames depend on the frame before them.
+        let all_independent = file_info.basis_format == BasisTextureFormat::UAstc;
+        if !all_independent && iframes.first() == Some(&false) {
+            return Err(BasisError::InvalidFile);
+        }
+
+        Ok(Self {
+            prepared,
+            state: TranscoderState::new(),
+            level_index,
+            format,
+            decode_flags,
+            us_per_frame: file_info.us_per_frame,
+            iframes,
+            all_independent,
+            next_frame: 0,
+        })
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        self.iframes.len() as u32
+    }
+
+    /// Time between two consecutive frames.
+    pub fn frame_duration(&self) -> Duration {
+        Duration::from_micros(self.us_per_frame as u64)
+    }
+
+    /// Total running time of the video.
+    pub fn duration(&self) -> Duration {
+        self.timestamp(self.frame_count())
+    }
+
+    /// Presentation time of the given frame.
+    pub fn timestamp(&self, frame_index: u32) -> Duration {
+        Duration::from_micros(self.us_per_frame as u64 * frame_index as u64)
+    }
+
+    /// Index of the frame the iterator will decode next.
+    pub fn next_frame_index(&self) -> u32 {
+        self.next_frame
+    }
+
+    /// Whether the file flags the given frame as an I-frame.
+    pub fn is_iframe(&self, frame_index: u32) -> bool {
+        self.iframes.get(frame_index as usize).cloned().unwrap_or(false)
+    }
+
+    /// Whether the given frame can be decoded without decoding any previous frame.
+    pub fn is_independent(&self, frame_index: u32) -> bool {
+        frame_index < self.frame_count() && (self.all_independent || self.is_iframe(frame_index))
+    }
+
+    /// Index of the frame that should be on screen at `timestamp`, clamped to the last frame.
+    pub fn frame_at(&self, timestamp: Duration) -> u32 {
+        if self.us_per_frame == 0 || self.frame_count() == 0 {
+            return 0;
+        }
+
+        let frame = timestamp.as_micros() / self.us_per_frame as u128;
+
+        frame.min(self.frame_count() as u128 - 1) as u32
+    }
+
+    /// Closest I-frame at or before the given frame.
+    pub fn previous_iframe(&self, frame_index: u32) -> Option<u32> {
+        let last = frame_index.min(self.frame_count().checked_sub(1)?);
+
+        (0..=last).rev().find(|&frame| self.iframes[frame as usize])
+    }
+
+    /// Closest independent frame at or before the given frame.
+    pub fn previous_independent(&self, frame_index: u32) -> Option<u32> {
+        let last = frame_index.min(self.frame_count().checked_sub(1)?);
+
+        (0..=last).rev().find(|&frame| self.is_independent(frame))
+    }
+
+    /// Decodes the given frame, starting from the closest independent frame before it.
+    ///
+    /// If the decoder is already between that frame and the requested frame, decoding continues from where
+    /// it is instead. Iteration continues from the frame after the requested one.
+    pub fn seek(&mut self, frame_index: u32) -> Result<VideoFrame, BasisError> {
+        self.check_frame(frame_index)?;
+
+        let independent = self
+            .previous_independent(frame_index)
+            .ok_or(BasisError::OutOfOrderFrame {
+                requested: frame_index,
+                expected: 0,
+            })?;
+
+        let start = if independent < self.next_frame && self.next_frame <= frame_index {
+            self.next_frame
+        } else {
+            independent
+        };
+
+        if start < frame_index {
+            // Frames we skip over only need to update the state, so reuse one buffer for all of them.
+            let size = self.prepared.transcoded_size(start, self.level_index, self.format)?;
+            let mut scratch = vec![0; size];
+
+            for frame in start..frame_index {
+                self.prepared.transcode_image_level_into_with_state(
+                    &mut self.state,
+                    frame,
+                    self.level_index,
+                    self.format,
+                    self.decode_flags,
+                    &mut scratch,
+                )?;
+                self.next_frame = frame + 1;
+            }
+        }
+
+        self.decode_frame(frame_index)
+    }
+
+    /// Decodes the frame that should be on screen at `timestamp`. See [`seek`](Self::seek).
+    pub fn seek_to_time(&mut self, timestamp: Duration) -> Result<VideoFrame, BasisError> {
+        self.seek(self.frame_at(timestamp))
+    }
+
+    /// Decodes a single frame.
+    ///
+    /// Independent frames can always be decoded. P-frames can only be decoded directly after the frame
+    /// before them.
+    pub fn decode_frame(&mut self, frame_index: u32) -> Result<VideoFrame, BasisError> {
+        self.check_frame(frame_index)?;
+
+        let independent = self.is_independent(frame_index);
+
+        if !independent && frame_index != self.next_frame {
+            return Err(BasisError::OutOfOrderFrame {
+                requested: frame_index,
+                expected: self.next_frame,
+            });
+        }
+
+        let data = self.prepared.transcode_image_level_with_state(
+            &mut self.state,
+            frame_index,
+            self.level_index,
+            self.format,
+            self.decode_flags,
+        )?;
+
+        self.next_frame = frame_index + 1;
+
+        Ok(VideoFrame {
+            frame_index,
+            timestamp: self.timestamp(frame_index),
+            iframe: self.is_iframe(frame_index),
+            independent,
+            data,
+        })
+    }
+
+    fn check_frame(&self, frame_index: u32) -> Result<(), BasisError> {
+        if frame_index < self.frame_count() {
+            Ok(())
+        } else {
+            Err(BasisError::FrameOutOfRange {
+                frame: frame_index,
+                frame_count: self.frame_count(),
+            })
+        }
+    }
+}
+
+impl<'p, 'a> Iterator for VideoDecoder<'p, 'a> {
+    type Item = Result<VideoFrame, BasisError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_frame >= self.frame_count() {
+            return None;
+        }
+
+        let frame = self.decode_frame(self.next_frame);
+
+        if frame.is_err() {
+            // Don't get stuck retrying the same frame forever.
+            self.next_frame = self.frame_count();
+        }
+
+        Some(frame)
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{BasisError, BasisTextureFormat, FileInfo, TargetTextureFormat, TextureType};
+use wgpu_types::{Extent3d, Features, TextureDescriptor, TextureDimension, TextureFormat, TextureUsage};
+
+impl TargetTextureFormat {
+    /// wgpu format holding this format's blocks, or `None` if wgpu has no equivalent.
+    ///
+    /// `srgb` picks the sRGB variant of the format where there is one.
+    pub fn wgpu_format(self, srgb: bool) -> Option<TextureFormat> {
+        let (linear, srgb_format) = match self {
+            Self::Etc1Rgb => (TextureFormat::Etc2RgbUnorm, TextureFormat::Etc2RgbUnormSrgb),
+            Self::Etc2Rgba => (TextureFormat::Etc2RgbA8Unorm, TextureFormat::Etc2RgbA8UnormSrgb),
+            Self::Bc1Rgb => (TextureFormat::Bc1RgbaUnorm, TextureFormat::Bc1RgbaUnormSrgb),
+            Self::Bc3Rgba => (TextureFormat::Bc3RgbaUnorm, TextureFormat::Bc3RgbaUnormSrgb),
+            Self::Bc4R => return Some(TextureFormat::Bc4RUnorm),
+            Self::Bc5Rg => return Some(TextureFormat::Bc5RgUnorm),
+            Self::Bc7Rgba => (TextureFormat::Bc7RgbaUnorm, TextureFormat::Bc7RgbaUnormSrgb),
+            Self::AstcRgba => (TextureFormat::Astc4x4RgbaUnorm, TextureFormat::Astc4x4RgbaUnormSrgb),
+            Self::EacR11 => return Some(TextureFormat::EacRUnorm),
+            Self::EacRg11 => return Some(TextureFormat::EtcRgUnorm),
+            Self::Rgba32 => (TextureFormat::Rgba8Unorm, TextureFormat::Rgba8UnormSrgb),
+            _ => return None,
+        };
+
+        Some(if srgb { srgb_format } else { linear })
+    }
+
+    /// Features a wgpu device needs to create textures of this format, or `None` if wgpu has no equivalent.
+    pub fn wgpu_features(self) -> Option<Features> {
+        Some(match self {
+            Self::Bc1Rgb | Self::Bc3Rgba | Self::Bc4R | Self::Bc5Rg | Self::Bc7Rgba => Features::TEXTURE_COMPRESSION_BC,
+            Self::Etc1Rgb | Self::Etc2Rgba | Self::EacR11 | Self::EacRg11 => Features::TEXTURE_COMPRESSION_ETC2,
+            Self::AstcRgba => Features::TEXTURE_COMPRESSION_ASTC_LDR,
+            Self::Rgba32 => Features::empty(),
+            _ => return None,
+        })
+    }
+}
+
+impl BasisTextureFormat {
+    /// Smallest target with good quality that a wgpu device with `features` can sample, falling back to
+    /// [`TargetTextureFormat::Rgba32`].
+    ///
+    /// UASTC prefers ASTC and BC7, which it transcodes to almost losslessly. ETC1S prefers the smaller BC1,
+    /// BC3 and ETC formats, which hold everything ETC1S can express.
+    #[allow(clippy::unnecessary_map_or)] // msrv doesn't allow this
+    pub fn best_wgpu_target(self, features: Features, has_alpha: bool) -> TargetTextureFormat {
+        let etc = if has_alpha {
+            TargetTextureFormat::Etc2Rgba
+        } else {
+            TargetTextureFormat::Etc1Rgb
+        };
+        let bc = if has_alpha {
+            TargetTextureFormat::Bc3Rgba
+        } else {
+            TargetTextureFormat::Bc1Rgb
+        };
+
+        let preferred: [TargetTextureFormat; 3] = match self {
+            Self::UAstc => [TargetTextureFormat::AstcRgba, TargetTextureFormat::Bc7Rgba, etc],
+            Self::Etc1s => [bc, etc, TargetTextureFormat::AstcRgba],
+        };
+
+        preferred
+            .iter()
+            .copied()
+            .find(|format| format.wgpu_features().map_or(false, |needed| features.contains(needed)))
+            .unwrap_or(TargetTextureFormat::Rgba32)
+    }
+}
+
+impl FileInfo {
+    /// Describes a texture holding every image and level of the file transcoded to `format`, usable as
+    /// [`TextureUsage::SAMPLED`] and [`TextureUsage::COPY_DST`].
+    ///
+    /// Arrays, videos and cubemaps become 2D textures with one layer per image, and volumes become 3D
+    /// textures. Compressed formats are rounded up to whole blocks, which is what the transcoder writes.
+    ///
+    /// Mipmapped volumes return [`BasisError::UnsupportedTextureType`], as their levels keep every slice
+    /// while the mips of a 3D texture halve in depth.
+    pub fn wgpu_texture_descriptor<'l>(
+        &self,
+        label: Option<&'l str>,
+        format: TargetTextureFormat,
+        srgb: bool,
+    ) -> Result<TextureDescriptor<Option<&'l str>>, BasisError> {
+        let wgpu_format = format
+            .wgpu_format(srgb)
+            .ok_or(BasisError::UnsupportedOutputFormat(format))?;
+
+        let slice = self
+            .slice_info
+            .iter()
+            .find(|slice| slice.image_index == 0 && slice.level_index == 0)
+            .ok_or(BasisError::InvalidFile)?;
+        let (width, height) = if format.is_uncompressed() {
+            (slice.orig_width, slice.orig_height)
+        } else {
+            (slice.num_blocks_x * 4, slice.num_blocks_y * 4)
+        };
+
+        let (dimension, depth) = match self.tex_type {
+            TextureType::D2 => (TextureDimension::D2, 1),
+            TextureType::D2Array | TextureType::CubemapArray | TextureType::VideoFrames => {
+                (TextureDimension::D2, self.total_images)
+            }
+            TextureType::D3 if self.image_mipmap_levels[0] > 1 => {
+                return Err(BasisError::UnsupportedTextureType(TextureType::D3))
+            }
+            TextureType::D3 => (TextureDimension::D3, self.total_images),
+            tex_type => return Err(BasisError::UnsupportedTextureType(tex_type)),
+        };
+
+        Ok(TextureDescriptor {
+            label,
+            size: Extent3d { width, height, depth },
+            mip_level_count: self.image_mipmap_levels[0],
+            sample_count: 1,
+            dimension,
+            format: wgpu_format,
+            usage: TextureUsage::SAMPLED | TextureUsage::COPY_DST,
+        })
+    }
+}
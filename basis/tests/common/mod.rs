@@ -0,0 +1,27 @@
+//! Fixtures shared by the integration tests. Each test crate only uses some of them.
+#![allow(dead_code)]
+
+use basis::{Compressor, MipmapOptions, SourceImage, TextureType};
+
+/// Encodes `count` flat grey images into a `.basis` file of the given type, with every mip level.
+pub fn compress(texture_type: TextureType, width: u32, height: u32, count: usize) -> Vec<u8> {
+    let pixels = vec![200; (width * height * 4) as usize];
+
+    Compressor::new()
+        .texture_type(texture_type)
+        .mipmaps(Some(MipmapOptions::default()))
+        .compress(&vec![SourceImage::new(width, height, &pixels); count])
+        .unwrap()
+        .data
+}
+
+/// basis_universal's CRC-16, which checksums the header and the data of `.basis` files.
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = !0u16;
+    for &byte in data {
+        let q = byte as u16 ^ (crc >> 8);
+        let k = (q >> 4) ^ q;
+        crc = (crc << 8) ^ k ^ (k << 5) ^ (k << 12);
+    }
+    !crc
+}
@@ -0,0 +1,452 @@
+mod common;
+
+use basis::{
+    BasisError, BasisTextureFormat, CancellationToken, CompressionPhase, Compressor, Container, DecodeFlags, MipFilter,
+    MipmapOptions, SharedCodebook, SourceImage, TargetTextureFormat, TextureType, UastcRdo, UserData,
+};
+use common::crc16;
+use std::{
+    sync::{mpsc, Arc},
+    thread,
+};
+
+fn gradient(width: u32, height: u32) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            pixels.extend_from_slice(&[(x * 255 / width) as u8, (y * 255 / height) as u8, 128, 255]);
+        }
+    }
+    pixels
+}
+
+fn round_trip(basis_format: BasisTextureFormat) {
+    let pixels = gradient(64, 32);
+
+    let file = Compressor::new()
+        .basis_format(basis_format)
+        .y_flip(true)
+        .userdata(UserData { word0: 1, word1: 2 })
+        .compress(&[SourceImage::new(64, 32, &pixels)])
+        .unwrap();
+    assert_eq!(file.container, Container::Basis);
+    assert_eq!(file.basis_file_size, file.data.len());
+    assert_eq!(file.ktx2_file_size, None);
+    let file = file.data;
+
+    let transcoder = basis::Transcoder::new();
+    assert_eq!(transcoder.validate_file_checksums(&file, true), Ok(()));
+
+    let file_info = transcoder.get_file_info(&file).unwrap();
+    assert_eq!(file_info.basis_format, basis_format);
+    assert_eq!(file_info.total_images, 1);
+    assert_eq!(file_info.image_mipmap_levels, vec![1]);
+    assert!(file_info.y_flipped);
+    assert_eq!(file_info.userdata, UserData { word0: 1, word1: 2 });
+
+    let level_info = transcoder.get_basic_image_level_info(&file, 0, 0).unwrap();
+    assert_eq!((level_info.orig_width, level_info.orig_height), (64, 32));
+
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let rgba = prepared
+        .transcode_image_level(0, 0, TargetTextureFormat::Rgba32, DecodeFlags::empty())
+        .unwrap();
+    assert_eq!(rgba.len(), pixels.len());
+}
+
+#[test]
+fn compress_etc1s() {
+    round_trip(BasisTextureFormat::Etc1s);
+}
+
+#[test]
+fn compress_uastc() {
+    round_trip(BasisTextureFormat::UAstc);
+}
+
+#[test]
+fn compress_rejects_invalid_images() {
+    let pixels = gradient(16, 16);
+
+    assert_eq!(Compressor::new().compress(&[]), Err(BasisError::NoSourceImages));
+    assert_eq!(
+        Compressor::new().compress(&[SourceImage::new(16, 16, &pixels), SourceImage::new(16, 8, &pixels),]),
+        Err(BasisError::InvalidSourceImage { image_index: 1 })
+    );
+    assert_eq!(
+        Compressor::new().compress(&[SourceImage::new(0, 0, &[])]),
+        Err(BasisError::InvalidSourceImage { image_index: 0 })
+    );
+    // 2^32 pixels, whose size would wrap around in 32 bits.
+    assert_eq!(
+        Compressor::new().compress(&[SourceImage::new(65536, 65536, &pixels)]),
+        Err(BasisError::InvalidSourceImage { image_index: 0 })
+    );
+}
+
+#[test]
+fn compress_rejects_invalid_parameters() {
+    let pixels = gradient(16, 16);
+    let images = [SourceImage::new(16, 16, &pixels)];
+    #[allow(clippy::legacy_numeric_constants)] // msrv doesn't allow this
+    let nan = std::f32::NAN;
+
+    assert_eq!(
+        Compressor::new().quality_level(0).compress(&images),
+        Err(BasisError::InvalidParameter("quality_level"))
+    );
+    assert_eq!(
+        Compressor::new()
+            .uastc_rdo(Some(UastcRdo {
+                lambda: nan,
+                ..Default::default()
+            }))
+            .compress(&images),
+        Err(BasisError::InvalidParameter("uastc_rdo.lambda"))
+    );
+    assert_eq!(
+        Compressor::new()
+            .uastc_rdo(Some(UastcRdo {
+                dictionary_size: 32,
+                ..Default::default()
+            }))
+            .compress(&images),
+        Err(BasisError::InvalidParameter("uastc_rdo.dictionary_size"))
+    );
+    assert_eq!(
+        Compressor::new().zstd_level(Some(23)).compress(&images),
+        Err(BasisError::InvalidParameter("zstd_level"))
+    );
+    assert_eq!(
+        Compressor::new()
+            .mipmaps(Some(MipmapOptions {
+                smallest_dimension: 0,
+                ..Default::default()
+            }))
+            .compress(&images),
+        Err(BasisError::InvalidParameter("mipmaps.smallest_dimension"))
+    );
+}
+
+#[test]
+fn compress_texture_types() {
+    let square = gradient(16, 16);
+    let wide = gradient(32, 16);
+
+    let file = Compressor::new()
+        .texture_type(TextureType::CubemapArray)
+        .compress(&[SourceImage::new(16, 16, &square); 6])
+        .unwrap()
+        .data;
+
+    let transcoder = basis::Transcoder::new();
+    assert_eq!(transcoder.get_texture_type(&file), Ok(TextureType::CubemapArray));
+    assert_eq!(transcoder.get_total_images(&file).unwrap().get(), 6);
+
+    let file = Compressor::new()
+        .texture_type(TextureType::VideoFrames)
+        .us_per_frame(33_333)
+        .compress(&[SourceImage::new(16, 16, &square); 3])
+        .unwrap()
+        .data;
+
+    let file_info = transcoder.get_file_info(&file).unwrap();
+    assert_eq!(file_info.tex_type, TextureType::VideoFrames);
+    assert_eq!(file_info.us_per_frame, 33_333);
+
+    assert_eq!(
+        Compressor::new()
+            .texture_type(TextureType::D2Array)
+            .compress(&[SourceImage::new(16, 16, &square), SourceImage::new(32, 16, &wide)]),
+        Err(BasisError::InconsistentImages { image_index: 1 })
+    );
+    assert_eq!(
+        Compressor::new()
+            .texture_type(TextureType::CubemapArray)
+            .compress(&[SourceImage::new(16, 16, &square); 5]),
+        Err(BasisError::InvalidCubemapImageCount(5))
+    );
+    assert_eq!(
+        Compressor::new()
+            .texture_type(TextureType::CubemapArray)
+            .compress(&[SourceImage::new(32, 16, &wide); 6]),
+        Err(BasisError::NonSquareCubemapFace { image_index: 0 })
+    );
+    assert!(Compressor::new()
+        .compress(&[SourceImage::new(16, 16, &square), SourceImage::new(32, 16, &wide)])
+        .is_ok());
+}
+
+#[test]
+fn compress_uastc_rdo_ktx2() {
+    let pixels = gradient(64, 64);
+    let images = [SourceImage::new(64, 64, &pixels)];
+
+    let compressor = Compressor::new()
+        .basis_format(BasisTextureFormat::UAstc)
+        .container(Container::Ktx2)
+        .zstd_level(Some(9));
+
+    let plain = compressor.compress(&images).unwrap();
+    let rdo = compressor
+        .uastc_rdo(Some(UastcRdo::default()))
+        .compress(&images)
+        .unwrap();
+
+    assert_eq!(rdo.container, Container::Ktx2);
+    assert_eq!(rdo.ktx2_file_size, Some(rdo.data.len()));
+    assert!(rdo.ktx2_file_size < plain.ktx2_file_size);
+
+    let mut transcoder = basis::Ktx2Transcoder::new(&rdo.data).unwrap();
+    assert_eq!(transcoder.get_tex_format(), BasisTextureFormat::UAstc);
+    assert_eq!((transcoder.get_width(), transcoder.get_height()), (64, 64));
+    assert!(transcoder.get_color_info().is_srgb());
+
+    let rgba = transcoder
+        .transcode_image_level(0, 0, 0, TargetTextureFormat::Rgba32, DecodeFlags::empty())
+        .unwrap();
+    assert_eq!(rgba.len(), pixels.len());
+}
+
+#[test]
+fn compress_progress_and_cancellation() {
+    let pixels = gradient(16, 16);
+    let images = [SourceImage::new(16, 16, &pixels); 4];
+    let compressor = Compressor::new().texture_type(TextureType::D2Array);
+
+    let mut reports = Vec::new();
+    compressor
+        .compress_with_progress(&images, None, |progress| reports.push(progress))
+        .unwrap();
+
+    let phases: Vec<_> = reports.iter().map(|progress| progress.phase).collect();
+    assert_eq!(
+        phases,
+        vec![
+            CompressionPhase::LoadingImages,
+            CompressionPhase::LoadingImages,
+            CompressionPhase::LoadingImages,
+            CompressionPhase::LoadingImages,
+            CompressionPhase::PreparingImages,
+            CompressionPhase::GeneratingCodebooks,
+            CompressionPhase::EncodingSlices,
+            CompressionPhase::Rdo,
+            CompressionPhase::WritingFile,
+            CompressionPhase::Done,
+        ]
+    );
+    let images_loaded: Vec<_> = reports.iter().map(|progress| progress.images_loaded).collect();
+    assert_eq!(images_loaded, vec![0, 1, 2, 3, 4, 4, 4, 4, 4, 4]);
+
+    let mut phases = Vec::new();
+    Compressor::new()
+        .basis_format(BasisTextureFormat::UAstc)
+        .uastc_rdo(Some(UastcRdo::default()))
+        .compress_with_progress(&images[..1], None, |progress| phases.push(progress.phase))
+        .unwrap();
+    assert_eq!(
+        phases,
+        vec![
+            CompressionPhase::LoadingImages,
+            CompressionPhase::PreparingImages,
+            CompressionPhase::EncodingSlices,
+            CompressionPhase::WritingFile,
+            CompressionPhase::Done,
+        ]
+    );
+
+    // Cancelling while codebooks are generated stops the encode before the slices are encoded.
+    let cancellation = CancellationToken::new();
+    let mut phases = Vec::new();
+    let result = compressor.compress_with_progress(&images, Some(&cancellation), |progress| {
+        phases.push(progress.phase);
+        if progress.phase == CompressionPhase::GeneratingCodebooks {
+            cancellation.cancel();
+        }
+    });
+    assert_eq!(result, Err(BasisError::Cancelled));
+    assert_eq!(phases.last(), Some(&CompressionPhase::GeneratingCodebooks));
+
+    // A token cancelled up front stops before anything is loaded.
+    let mut reports = 0;
+    let result = compressor.compress_with_progress(&images, Some(&cancellation), |_| reports += 1);
+    assert_eq!(result, Err(BasisError::Cancelled));
+    assert_eq!(reports, 0);
+}
+
+#[test]
+fn compress_cancelled_from_another_thread() {
+    let pixels = gradient(64, 64);
+    let images = [SourceImage::new(64, 64, &pixels); 8];
+    let cancellation = CancellationToken::new();
+
+    let (started_tx, started_rx) = mpsc::channel();
+    let (cancelled_tx, cancelled_rx) = mpsc::channel();
+    let canceller = {
+        let cancellation = cancellation.clone();
+        thread::spawn(move || {
+            started_rx.recv().unwrap();
+            cancellation.cancel();
+            cancelled_tx.send(()).unwrap();
+        })
+    };
+
+    let mut phases = Vec::new();
+    let result = Compressor::new()
+        .texture_type(TextureType::D2Array)
+        .compress_with_progress(&images, Some(&cancellation), |progress| {
+            phases.push(progress.phase);
+            // Wait for the other thread to cancel, so the encode is stopped at the next phase.
+            if progress.phase == CompressionPhase::PreparingImages {
+                started_tx.send(()).unwrap();
+                cancelled_rx.recv().unwrap();
+            }
+        });
+    canceller.join().unwrap();
+
+    assert_eq!(result, Err(BasisError::Cancelled));
+    assert_eq!(phases.last(), Some(&CompressionPhase::PreparingImages));
+}
+
+#[test]
+fn compress_stats() {
+    let pixels = gradient(64, 64);
+    let images = [SourceImage::new(64, 64, &pixels), SourceImage::new(64, 64, &pixels)];
+
+    let file = Compressor::new()
+        .texture_type(TextureType::D2Array)
+        .compute_stats(true)
+        .compress(&images)
+        .unwrap();
+    let stats = file.stats.unwrap();
+
+    let file_info = basis::Transcoder::new().get_file_info(&file.data).unwrap();
+    assert_eq!(stats.total_endpoints, file_info.total_endpoints);
+    assert_eq!(stats.total_selectors, file_info.total_selectors);
+    assert_eq!(stats.endpoint_codebook_size, file_info.endpoint_codebook_size);
+    assert_eq!(stats.selector_codebook_size, file_info.selector_codebook_size);
+    assert!(stats.bits_per_texel > 0.0);
+
+    let indices: Vec<_> = stats
+        .levels
+        .iter()
+        .map(|level| (level.image_index, level.level_index))
+        .collect();
+    assert_eq!(indices, vec![(0, 0), (1, 0)]);
+    for level in &stats.levels {
+        assert_eq!((level.width, level.height), (64, 64));
+        assert!(level.rgb_psnr > 30.0);
+        assert!(level.luma_ssim > 0.9 && level.luma_ssim <= 1.0);
+    }
+
+    assert_eq!(Compressor::new().compress(&images[..1]).unwrap().stats, None);
+}
+
+#[test]
+fn compress_mipmaps() {
+    let pixels = gradient(64, 32);
+    let images = [SourceImage::new(64, 32, &pixels)];
+    let transcoder = basis::Transcoder::new();
+
+    let file = Compressor::new()
+        .mipmaps(Some(MipmapOptions::default()))
+        .compress(&images)
+        .unwrap()
+        .data;
+    assert_eq!(transcoder.get_total_image_levels(&file, 0), Ok(7));
+    assert_eq!(transcoder.get_file_info(&file).unwrap().image_mipmap_levels, vec![7]);
+    let smallest = transcoder.get_basic_image_level_info(&file, 0, 6).unwrap();
+    assert_eq!((smallest.orig_width, smallest.orig_height), (1, 1));
+
+    let file = Compressor::new()
+        .mipmaps(Some(MipmapOptions {
+            filter: MipFilter::Lanczos4,
+            srgb: false,
+            wrap: true,
+            renormalize: true,
+            smallest_dimension: 8,
+        }))
+        .compress(&images)
+        .unwrap()
+        .data;
+    assert_eq!(transcoder.get_total_image_levels(&file, 0), Ok(4));
+    let smallest = transcoder.get_basic_image_level_info(&file, 0, 3).unwrap();
+    assert_eq!((smallest.orig_width, smallest.orig_height), (8, 4));
+}
+
+#[test]
+fn compress_shared_codebook() {
+    let small = gradient(32, 32);
+    let large = gradient(64, 32);
+    let images = [SourceImage::new(32, 32, &small), SourceImage::new(64, 32, &large)];
+
+    let codebook = Arc::new(SharedCodebook::build(&Compressor::new(), &images).unwrap());
+    let reloaded = SharedCodebook::from_basis_file(codebook.file().to_vec()).unwrap();
+    assert_eq!(reloaded.file(), codebook.file());
+
+    let file = Compressor::new()
+        .shared_codebook(Some(codebook.clone()))
+        .compress(&images[1..])
+        .unwrap();
+    let file_info = basis::Transcoder::new().get_file_info(&file.data).unwrap();
+    assert_eq!(file_info.endpoint_codebook_size, 0);
+    assert_eq!(file_info.selector_codebook_size, 0);
+
+    assert!(basis::Transcoder::new().prepare_transcoding(&file.data).is_err());
+
+    let transcoder = basis::Transcoder::with_shared_codebook(codebook.clone());
+    let mut prepared = transcoder.prepare_transcoding(&file.data).unwrap();
+    let rgba = prepared
+        .transcode_image_level(0, 0, TargetTextureFormat::Rgba32, DecodeFlags::empty())
+        .unwrap();
+    assert_eq!(rgba.len(), large.len());
+
+    assert_eq!(
+        Compressor::new()
+            .basis_format(BasisTextureFormat::UAstc)
+            .shared_codebook(Some(codebook))
+            .compress(&images[..1]),
+        Err(BasisError::UnsupportedBasisFormat(BasisTextureFormat::UAstc))
+    );
+}
+
+/// Overwrites part of a `.basis` file's header, keeping the header checksum valid.
+fn patch_header(file: &[u8], offset: usize, bytes: &[u8]) -> Vec<u8> {
+    let mut file = file.to_vec();
+    file[offset..offset + bytes.len()].copy_from_slice(bytes);
+    let header_size = u16::from_le_bytes([file[4], file[5]]) as usize;
+    let crc = crc16(&file[8..header_size]);
+    file[6..8].copy_from_slice(&crc.to_le_bytes());
+    file
+}
+
+#[test]
+fn shared_codebook_rejects_corrupted_files() {
+    let pixels = gradient(32, 32);
+    let codebook = SharedCodebook::build(&Compressor::new(), &[SourceImage::new(32, 32, &pixels)]).unwrap();
+    let file = codebook.file();
+
+    for length in &[file.len() / 2, file.len() - 1] {
+        assert!(SharedCodebook::from_basis_file(file[..*length].to_vec()).is_err());
+    }
+
+    // Header fields: endpoint codebook offset and size, selector codebook offset and size, tables offset and size.
+    let corruptions: &[(usize, &[u8])] = &[
+        (41, &[0x00, 0xff, 0xff, 0xff]),
+        (45, &[0xff, 0xff, 0xff]),
+        (45, &[0, 0, 0]),
+        (50, &[0x00, 0xff, 0xff, 0xff]),
+        (54, &[0xff, 0xff, 0xff]),
+        (57, &[0xff, 0xff, 0xff, 0xff]),
+        (61, &[0x00, 0x00, 0x00, 0x80]),
+        (61, &[0, 0, 0, 0]),
+    ];
+    for &(offset, bytes) in corruptions {
+        assert_eq!(
+            SharedCodebook::from_basis_file(patch_header(file, offset, bytes)).map(|_| ()),
+            Err(BasisError::InvalidFile),
+            "header offset {}",
+            offset
+        );
+    }
+}
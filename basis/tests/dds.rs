@@ -0,0 +1,113 @@
+#![cfg(feature = "dds")]
+
+mod common;
+
+use basis::{
+    ddsfile::{D3D10ResourceDimension, Dds, DxgiFormat},
+    BasisError, Compressor, DecodeFlags, SourceImage, TargetTextureFormat, TextureType,
+};
+use common::compress;
+
+#[test]
+fn dds_cubemap() {
+    let file = compress(TextureType::CubemapArray, 16, 16, 6);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let dds = prepared
+        .to_dds(TargetTextureFormat::Bc7Rgba, true, DecodeFlags::empty())
+        .unwrap();
+
+    assert_eq!(dds.get_dxgi_format(), Some(DxgiFormat::BC7_UNorm_sRGB));
+    assert_eq!((dds.get_width(), dds.get_height()), (16, 16));
+    assert_eq!(dds.get_num_mipmap_levels(), 5);
+    assert_eq!(dds.header10.as_ref().unwrap().array_size, 1);
+    // 4x4 + 2x2 + 1x1 + 1x1 + 1x1 blocks per face.
+    assert_eq!(dds.data.len(), 6 * 23 * 16);
+
+    let mut written = Vec::new();
+    dds.write(&mut written).unwrap();
+    assert_eq!(Dds::read(&mut &written[..]).unwrap().data, dds.data);
+}
+
+#[test]
+fn dds_array() {
+    let file = compress(TextureType::D2Array, 16, 16, 3);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let dds = prepared
+        .to_dds(TargetTextureFormat::Rgba32, false, DecodeFlags::empty())
+        .unwrap();
+
+    assert_eq!(dds.get_dxgi_format(), Some(DxgiFormat::R8G8B8A8_UNorm));
+    assert_eq!(dds.header10.as_ref().unwrap().array_size, 3);
+    assert_eq!(dds.data.len(), 3 * (16 * 16 + 8 * 8 + 4 * 4 + 2 * 2 + 1) * 4);
+    assert_eq!(
+        &dds.data[..16 * 16 * 4],
+        &prepared
+            .transcode_image_level(0, 0, TargetTextureFormat::Rgba32, DecodeFlags::empty())
+            .unwrap()[..]
+    );
+
+    assert_eq!(
+        prepared
+            .to_dds(TargetTextureFormat::Etc1Rgb, false, DecodeFlags::empty())
+            .err(),
+        Some(BasisError::UnsupportedOutputFormat(TargetTextureFormat::Etc1Rgb))
+    );
+}
+
+#[test]
+fn dds_volume() {
+    let pixels = vec![200; 16 * 16 * 4];
+    let file = Compressor::new()
+        .texture_type(TextureType::D3)
+        .compress(&[SourceImage::new(16, 16, &pixels); 3])
+        .unwrap()
+        .data;
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let dds = prepared
+        .to_dds(TargetTextureFormat::Bc1Rgb, false, DecodeFlags::empty())
+        .unwrap();
+
+    assert_eq!(dds.get_depth(), 3);
+    assert_eq!(dds.get_num_mipmap_levels(), 1);
+    assert_eq!(
+        dds.header10.as_ref().unwrap().resource_dimension,
+        D3D10ResourceDimension::Texture3D
+    );
+    assert_eq!(dds.data.len(), 3 * 16 * 8);
+
+    // Mipmapped volumes are rejected, like every other export.
+    let file = compress(TextureType::D3, 16, 16, 3);
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    assert_eq!(
+        prepared
+            .to_dds(TargetTextureFormat::Bc1Rgb, false, DecodeFlags::empty())
+            .err(),
+        Some(BasisError::UnsupportedTextureType(TextureType::D3))
+    );
+}
+
+#[test]
+fn dds_format_matches_dxgi_format() {
+    for &format in &[
+        TargetTextureFormat::Bc1Rgb,
+        TargetTextureFormat::Bc4R,
+        TargetTextureFormat::Bc7Rgba,
+        TargetTextureFormat::Rgba32,
+        TargetTextureFormat::Rgb565,
+        TargetTextureFormat::Rgba4444,
+        TargetTextureFormat::AstcRgba,
+    ] {
+        for &srgb in &[false, true] {
+            assert_eq!(
+                format.dds_format(srgb).map(|dxgi_format| dxgi_format as u32),
+                format.dxgi_format(srgb)
+            );
+        }
+    }
+}
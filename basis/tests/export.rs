@@ -0,0 +1,212 @@
+mod common;
+
+use basis::{BasisError, Compressor, DecodeFlags, SourceImage, TargetTextureFormat, TextureType};
+use common::compress;
+use std::convert::TryInto;
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> usize {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap()) as usize
+}
+
+#[test]
+fn ktx_cubemap() {
+    let file = compress(TextureType::CubemapArray, 16, 16, 6);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let ktx = prepared
+        .to_ktx(TargetTextureFormat::Bc7Rgba, true, DecodeFlags::empty())
+        .unwrap();
+
+    assert_eq!(&ktx[1..4], b"KTX");
+    assert_eq!(read_u32(&ktx, 12), 0x0403_0201);
+    // glInternalFormat is GL_COMPRESSED_SRGB_ALPHA_BPTC_UNORM.
+    assert_eq!(read_u32(&ktx, 28), 0x8E8D);
+    // Width, height, depth, array elements, faces and levels.
+    let header: Vec<u32> = (36..60).step_by(4).map(|offset| read_u32(&ktx, offset)).collect();
+    assert_eq!(header, vec![16, 16, 0, 0, 6, 5]);
+    // The first level gives the size of a single face.
+    assert_eq!(read_u32(&ktx, 64), 16 * 16);
+    // 4x4 + 2x2 + 1x1 + 1x1 + 1x1 blocks per face.
+    assert_eq!(ktx.len(), 64 + 5 * 4 + 6 * 23 * 16);
+}
+
+#[test]
+fn ktx_pads_uncompressed_rows() {
+    let file = Compressor::new()
+        .compress(&[SourceImage::new(5, 3, &[100; 5 * 3 * 4])])
+        .unwrap()
+        .data;
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let ktx = prepared
+        .to_ktx(TargetTextureFormat::Rgb565, false, DecodeFlags::empty())
+        .unwrap();
+    let rgb565 = prepared
+        .transcode_image_level(0, 0, TargetTextureFormat::Rgb565, DecodeFlags::empty())
+        .unwrap();
+
+    // glTypeSize is 2 for 16 bit packed pixels.
+    assert_eq!(read_u32(&ktx, 20), 2);
+    // Rows of 10 bytes are padded to 12.
+    assert_eq!(read_u32(&ktx, 64), 3 * 12);
+    assert_eq!(&ktx[68..78], &rgb565[..10]);
+    assert_eq!(&ktx[80..90], &rgb565[10..20]);
+    assert_eq!(ktx.len(), 68 + 3 * 12);
+}
+
+#[test]
+fn ktx2_array() {
+    let file = compress(TextureType::D2Array, 16, 16, 3);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let ktx2 = prepared
+        .to_ktx2(TargetTextureFormat::Rgba32, false, DecodeFlags::empty())
+        .unwrap();
+
+    assert_eq!(&ktx2[1..7], b"KTX 20");
+    // VK_FORMAT_R8G8B8A8_UNORM.
+    assert_eq!(read_u32(&ktx2, 12), 37);
+    // Width, height, depth, layers, faces, levels and supercompression.
+    let header: Vec<u32> = (20..48).step_by(4).map(|offset| read_u32(&ktx2, offset)).collect();
+    assert_eq!(header, vec![16, 16, 0, 3, 1, 5, 0]);
+
+    let dfd_offset = read_u32(&ktx2, 48) as usize;
+    assert_eq!(dfd_offset, 80 + 5 * 24);
+    // Four samples of 16 bytes after the 24 byte block header.
+    assert_eq!(read_u32(&ktx2, 52), 4 + 24 + 4 * 16);
+    assert_eq!(read_u32(&ktx2, dfd_offset), 4 + 24 + 4 * 16);
+
+    let level0_offset = read_u64(&ktx2, 80);
+    let level0_length = read_u64(&ktx2, 88);
+    assert_eq!(level0_length, 3 * 16 * 16 * 4);
+    assert_eq!(read_u64(&ktx2, 96), level0_length);
+    assert_eq!(level0_offset + level0_length, ktx2.len());
+
+    let layer1 = prepared
+        .transcode_image_level(1, 0, TargetTextureFormat::Rgba32, DecodeFlags::empty())
+        .unwrap();
+    let layer1_offset = level0_offset + 16 * 16 * 4;
+    assert_eq!(&ktx2[layer1_offset..layer1_offset + layer1.len()], &layer1[..]);
+
+    // The smallest level comes first.
+    let level4_offset = read_u64(&ktx2, 80 + 4 * 24);
+    assert_eq!(level4_offset, dfd_offset + 4 + 24 + 4 * 16);
+    assert_eq!(read_u64(&ktx2, 80 + 4 * 24 + 8), 3 * 4);
+}
+
+#[test]
+fn ktx2_volume() {
+    let slices: Vec<Vec<u8>> = (0..4u8).map(|slice| vec![slice * 60; 16 * 16 * 4]).collect();
+    let images: Vec<_> = slices.iter().map(|pixels| SourceImage::new(16, 16, pixels)).collect();
+    let file = Compressor::new()
+        .texture_type(TextureType::D3)
+        .compress(&images)
+        .unwrap()
+        .data;
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let ktx2 = prepared
+        .to_ktx2(TargetTextureFormat::Bc1Rgb, true, DecodeFlags::empty())
+        .unwrap();
+
+    // VK_FORMAT_BC1_RGB_SRGB_BLOCK.
+    assert_eq!(read_u32(&ktx2, 12), 132);
+    let header: Vec<u32> = (20..48).step_by(4).map(|offset| read_u32(&ktx2, offset)).collect();
+    assert_eq!(header, vec![16, 16, 4, 0, 1, 1, 0]);
+
+    // Every slice is kept, in order.
+    let level0_offset = read_u64(&ktx2, 80);
+    assert_eq!(read_u64(&ktx2, 88), 4 * 16 * 8);
+    for slice in 0..4 {
+        let expected = prepared
+            .transcode_image_level(slice, 0, TargetTextureFormat::Bc1Rgb, DecodeFlags::empty())
+            .unwrap();
+        let offset = level0_offset + slice as usize * 16 * 8;
+        assert_eq!(&ktx2[offset..offset + 16 * 8], &expected[..]);
+    }
+
+    assert_eq!(
+        prepared.to_ktx2(TargetTextureFormat::Fxt1Rgb, false, DecodeFlags::empty()),
+        Err(BasisError::UnsupportedOutputFormat(TargetTextureFormat::Fxt1Rgb))
+    );
+
+    // Mip levels of a volume would have to drop slices.
+    let file = compress(TextureType::D3, 16, 16, 4);
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let error = Err(BasisError::UnsupportedTextureType(TextureType::D3));
+    assert_eq!(
+        prepared.to_ktx(TargetTextureFormat::Bc1Rgb, true, DecodeFlags::empty()),
+        error
+    );
+    assert_eq!(
+        prepared.to_ktx2(TargetTextureFormat::Bc1Rgb, true, DecodeFlags::empty()),
+        error
+    );
+    assert_eq!(
+        prepared.to_pvr(TargetTextureFormat::Bc1Rgb, true, DecodeFlags::empty()),
+        error
+    );
+}
+
+#[test]
+fn pvr_cubemap() {
+    let file = compress(TextureType::CubemapArray, 16, 16, 6);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let pvr = prepared
+        .to_pvr(TargetTextureFormat::Pvrtc1Rgba, true, DecodeFlags::empty())
+        .unwrap();
+
+    assert_eq!(&pvr[..4], b"PVR\x03");
+    // PVRTC 4bpp RGBA in sRGB.
+    assert_eq!(read_u64(&pvr, 8), 3);
+    assert_eq!(read_u32(&pvr, 16), 1);
+    // Height, width, depth, surfaces, faces, levels and metadata size.
+    let header: Vec<u32> = (24..52).step_by(4).map(|offset| read_u32(&pvr, offset)).collect();
+    assert_eq!(header, vec![16, 16, 1, 1, 6, 5, 0]);
+
+    let face0 = prepared
+        .transcode_image_level(0, 0, TargetTextureFormat::Pvrtc1Rgba, DecodeFlags::empty())
+        .unwrap();
+    assert_eq!(&pvr[52..52 + face0.len()], &face0[..]);
+    let face1 = prepared
+        .transcode_image_level(1, 0, TargetTextureFormat::Pvrtc1Rgba, DecodeFlags::empty())
+        .unwrap();
+    assert_eq!(&pvr[52 + face0.len()..52 + 2 * face0.len()], &face1[..]);
+
+    // BC4 has no sRGB variant, so it stays linear.
+    let pvr = prepared
+        .to_pvr(TargetTextureFormat::Bc4R, true, DecodeFlags::empty())
+        .unwrap();
+    assert_eq!(read_u32(&pvr, 16), 0);
+
+    assert_eq!(
+        prepared.to_pvr(TargetTextureFormat::Rgb565, false, DecodeFlags::empty()),
+        Err(BasisError::UnsupportedOutputFormat(TargetTextureFormat::Rgb565))
+    );
+}
+
+#[test]
+fn astc_image_level() {
+    let file = compress(TextureType::D2, 20, 12, 1);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let astc = prepared.to_astc(0, 1, DecodeFlags::empty()).unwrap();
+
+    assert_eq!(read_u32(&astc, 0), 0x5CA1_AB13);
+    assert_eq!(&astc[4..16], &[4, 4, 1, 10, 0, 0, 6, 0, 0, 1, 0, 0]);
+    // 3x2 blocks of 16 bytes.
+    assert_eq!(astc.len(), 16 + 3 * 2 * 16);
+
+    assert!(prepared.to_astc(0, 5, DecodeFlags::empty()).is_err());
+}
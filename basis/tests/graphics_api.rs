@@ -0,0 +1,57 @@
+use basis::TargetTextureFormat;
+
+const ALL_FORMATS: [TargetTextureFormat; 21] = [
+    TargetTextureFormat::Etc1Rgb,
+    TargetTextureFormat::Etc2Rgba,
+    TargetTextureFormat::Bc1Rgb,
+    TargetTextureFormat::Bc3Rgba,
+    TargetTextureFormat::Bc4R,
+    TargetTextureFormat::Bc5Rg,
+    TargetTextureFormat::Bc7Rgba,
+    TargetTextureFormat::Pvrtc1Rgb,
+    TargetTextureFormat::Pvrtc1Rgba,
+    TargetTextureFormat::AstcRgba,
+    TargetTextureFormat::AtcRgb,
+    TargetTextureFormat::AtcRgbA,
+    TargetTextureFormat::Fxt1Rgb,
+    TargetTextureFormat::Pvrtc2Rgb,
+    TargetTextureFormat::Pvrtc2Rgba,
+    TargetTextureFormat::EacR11,
+    TargetTextureFormat::EacRg11,
+    TargetTextureFormat::Rgba32,
+    TargetTextureFormat::Rgb565,
+    TargetTextureFormat::Bgr565,
+    TargetTextureFormat::Rgba4444,
+];
+
+#[test]
+fn native_formats() {
+    assert_eq!(TargetTextureFormat::Bc7Rgba.vk_format(false), Some(145));
+    assert_eq!(TargetTextureFormat::Bc7Rgba.vk_format(true), Some(146));
+    assert_eq!(TargetTextureFormat::Bc7Rgba.dxgi_format(true), Some(99));
+    assert_eq!(TargetTextureFormat::Bc7Rgba.gl_internal_format(true), Some(0x8E8D));
+    assert_eq!(TargetTextureFormat::Bc7Rgba.metal_pixel_format(true), Some(153));
+
+    assert_eq!(TargetTextureFormat::AstcRgba.metal_pixel_format(false), Some(204));
+    assert_eq!(TargetTextureFormat::AstcRgba.metal_pixel_format(true), Some(186));
+
+    // Formats without an sRGB variant ignore `srgb`.
+    assert_eq!(TargetTextureFormat::Bc5Rg.vk_format(true), Some(141));
+    assert_eq!(TargetTextureFormat::EacR11.gl_internal_format(true), Some(0x9270));
+
+    assert_eq!(TargetTextureFormat::Fxt1Rgb.vk_format(false), None);
+    assert_eq!(TargetTextureFormat::Pvrtc1Rgba.dxgi_format(false), None);
+    assert_eq!(TargetTextureFormat::Pvrtc2Rgba.metal_pixel_format(false), None);
+}
+
+#[test]
+fn every_format_has_a_native_equivalent() {
+    for &format in &ALL_FORMATS {
+        assert!(format.gl_internal_format(false).is_some(), "{:?}", format);
+
+        // Every format D3D or Metal can sample is also a Vulkan format.
+        if format.dxgi_format(false).is_some() || format.metal_pixel_format(false).is_some() {
+            assert!(format.vk_format(false).is_some(), "{:?}", format);
+        }
+    }
+}
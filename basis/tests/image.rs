@@ -0,0 +1,44 @@
+#![cfg(feature = "image")]
+
+mod common;
+
+use basis::{
+    image::{DynamicImage, ImageDecoder},
+    BasisDecoder, DecodeFlags, TargetTextureFormat, TextureType,
+};
+use common::compress;
+
+#[test]
+fn transcode_rgba_image() {
+    let file = compress(TextureType::D2, 20, 12, 1);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let image = prepared.transcode_rgba_image(0, 1, DecodeFlags::empty()).unwrap();
+    let rgba = prepared
+        .transcode_image_level(0, 1, TargetTextureFormat::Rgba32, DecodeFlags::empty())
+        .unwrap();
+
+    assert_eq!(image.dimensions(), (10, 6));
+    assert_eq!(image.into_raw(), rgba);
+}
+
+#[test]
+fn basis_decoder() {
+    let file = compress(TextureType::D2, 20, 12, 1);
+
+    let decoder = BasisDecoder::new(&file[..]).unwrap();
+    assert_eq!(decoder.dimensions(), (20, 12));
+    let image = DynamicImage::from_decoder(decoder).unwrap().to_rgba8();
+    assert_eq!(image.dimensions(), (20, 12));
+
+    let decoder = BasisDecoder::with_image_level(&file[..], 0, 2).unwrap();
+    assert_eq!(decoder.dimensions(), (5, 3));
+    assert_eq!(
+        DynamicImage::from_decoder(decoder).unwrap().to_rgba8().dimensions(),
+        (5, 3)
+    );
+
+    assert!(BasisDecoder::with_image_level(&file[..], 0, 5).is_err());
+    assert!(BasisDecoder::new(&b"not a basis file"[..]).is_err());
+}
@@ -0,0 +1,174 @@
+use basis::{
+    BasisError, BasisTextureFormat, Compressor, Container, DecodeFlags, Ktx2Transcoder, MipmapOptions, SourceImage,
+    TargetTextureFormat, TextureType,
+};
+
+/// `KHR_DF_MODEL_ETC1S` and `KHR_DF_MODEL_UASTC` from the Khronos Data Format Specification.
+const KHR_DF_MODEL_ETC1S: u32 = 163;
+const KHR_DF_MODEL_UASTC: u32 = 166;
+
+fn color(image_index: u32) -> [u8; 4] {
+    [(image_index * 40) as u8, 255 - (image_index * 40) as u8, 64, 255]
+}
+
+fn solid_images(count: u32) -> Vec<Vec<u8>> {
+    (0..count)
+        .map(|image_index| color(image_index).iter().cycle().take(16 * 16 * 4).cloned().collect())
+        .collect()
+}
+
+fn assert_color(rgba: &[u8], image_index: u32) {
+    let color = color(image_index);
+    assert!(
+        rgba[..4]
+            .iter()
+            .zip(&color)
+            .all(|(&a, &b)| (a as i32 - b as i32).abs() <= 8),
+        "{:?} is not close to {:?}",
+        &rgba[..4],
+        color
+    );
+}
+
+fn check_key_values(transcoder: &Ktx2Transcoder<'_>) {
+    let key_values = transcoder.get_key_values().unwrap();
+    assert!(key_values.iter().any(|key_value| key_value.key == "KTXwriter"));
+
+    let writer = transcoder.get_key_value("KTXwriter").unwrap().unwrap();
+    assert!(!writer.is_empty());
+    assert_eq!(transcoder.get_key_value("KTXmissing"), Ok(None));
+}
+
+#[test]
+fn ktx2_etc1s_array() {
+    let pixels = solid_images(2);
+    let images: Vec<_> = pixels.iter().map(|pixels| SourceImage::new(16, 16, pixels)).collect();
+
+    let file = Compressor::new()
+        .texture_type(TextureType::D2Array)
+        .mipmaps(Some(MipmapOptions::default()))
+        .container(Container::Ktx2)
+        .compress(&images)
+        .unwrap();
+    assert_eq!(file.container, Container::Ktx2);
+
+    let mut transcoder = Ktx2Transcoder::new(&file.data).unwrap();
+    assert_eq!(transcoder.get_tex_format(), BasisTextureFormat::Etc1s);
+    assert_eq!((transcoder.get_width(), transcoder.get_height()), (16, 16));
+    assert_eq!(transcoder.get_total_levels(), 5);
+    assert_eq!(transcoder.get_total_layers(), 2);
+    assert!(transcoder.is_array());
+    assert_eq!(transcoder.get_total_faces(), 1);
+    assert!(!transcoder.is_video());
+
+    // ETC1S KTX2 files are supercompressed with BasisLZ.
+    let color_info = transcoder.get_color_info();
+    assert_eq!(color_info.color_model, KHR_DF_MODEL_ETC1S);
+    assert!(color_info.is_srgb());
+    assert!(!color_info.is_alpha_premultiplied());
+
+    check_key_values(&transcoder);
+
+    for level_index in 0..5 {
+        for layer_index in 0..2 {
+            let level_info = transcoder.get_image_level_info(level_index, layer_index, 0).unwrap();
+            assert_eq!(
+                (level_info.level_index, level_info.layer_index, level_info.face_index),
+                (level_index, layer_index, 0)
+            );
+            assert_eq!(level_info.orig_width, 16 >> level_index);
+
+            let rgba = transcoder
+                .transcode_image_level(
+                    level_index,
+                    layer_index,
+                    0,
+                    TargetTextureFormat::Rgba32,
+                    DecodeFlags::empty(),
+                )
+                .unwrap();
+            assert_eq!(rgba.len(), ((16 >> level_index) * (16 >> level_index) * 4) as usize);
+            assert_color(&rgba, layer_index);
+        }
+    }
+
+    let format = TargetTextureFormat::Bc1Rgb;
+    assert_eq!(
+        transcoder.transcode_image_level(0, 2, 0, format, DecodeFlags::empty()),
+        Err(BasisError::LayerOutOfRange {
+            layer_index: 2,
+            total_layers: 2
+        })
+    );
+    assert_eq!(
+        transcoder.transcode_image_level(0, 0, 1, format, DecodeFlags::empty()),
+        Err(BasisError::FaceOutOfRange {
+            face_index: 1,
+            total_faces: 1
+        })
+    );
+    assert_eq!(
+        transcoder.transcode_image_level(5, 0, 0, format, DecodeFlags::empty()),
+        Err(BasisError::LevelOutOfRange {
+            image_index: 0,
+            level_index: 5,
+            total_levels: 5
+        })
+    );
+}
+
+#[test]
+fn ktx2_uastc_zstd_cubemap() {
+    let pixels = solid_images(6);
+    let images: Vec<_> = pixels.iter().map(|pixels| SourceImage::new(16, 16, pixels)).collect();
+
+    let file = Compressor::new()
+        .basis_format(BasisTextureFormat::UAstc)
+        .texture_type(TextureType::CubemapArray)
+        .container(Container::Ktx2)
+        .zstd_level(Some(3))
+        .compress(&images)
+        .unwrap();
+
+    let mut transcoder = Ktx2Transcoder::new(&file.data).unwrap();
+    assert_eq!(transcoder.get_tex_format(), BasisTextureFormat::UAstc);
+    assert_eq!(transcoder.get_total_levels(), 1);
+    assert_eq!(transcoder.get_total_layers(), 1);
+    assert!(!transcoder.is_array());
+    assert_eq!(transcoder.get_total_faces(), 6);
+
+    let color_info = transcoder.get_color_info();
+    assert_eq!(color_info.color_model, KHR_DF_MODEL_UASTC);
+    assert!(color_info.is_srgb());
+
+    check_key_values(&transcoder);
+
+    for face_index in 0..6 {
+        let format = TargetTextureFormat::Rgba32;
+        let size = transcoder.transcoded_size(0, 0, face_index, format).unwrap();
+        assert_eq!(size, 16 * 16 * 4);
+
+        let mut rgba = vec![0; size];
+        let written = transcoder
+            .transcode_image_level_into(0, 0, face_index, format, DecodeFlags::empty(), &mut rgba)
+            .unwrap();
+        assert_eq!(written, size);
+        assert_color(&rgba, face_index);
+    }
+
+    let format = TargetTextureFormat::Bc7Rgba;
+    assert_eq!(
+        transcoder.transcode_image_level(0, 0, 6, format, DecodeFlags::empty()),
+        Err(BasisError::FaceOutOfRange {
+            face_index: 6,
+            total_faces: 6
+        })
+    );
+    assert_eq!(
+        transcoder.transcode_image_level(0, 1, 0, format, DecodeFlags::empty()),
+        Err(BasisError::LayerOutOfRange {
+            layer_index: 1,
+            total_layers: 1
+        })
+    );
+}
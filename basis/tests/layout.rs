@@ -0,0 +1,69 @@
+use basis::{BasisError, DecodeFlags, StagingLayoutBuilder, TargetTextureFormat};
+
+#[test]
+#[allow(clippy::manual_div_ceil)] // msrv doesn't allow this
+fn aligned_staging_layout() {
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&image).unwrap();
+
+    let format = TargetTextureFormat::Bc1Rgb;
+    let layout = StagingLayoutBuilder::new()
+        .row_pitch_alignment(256)
+        .subresource_alignment(512)
+        .build(&prepared, format)
+        .unwrap();
+
+    assert_eq!(layout.subresources.len(), 12);
+    for (level, subresource) in layout.subresources.iter().enumerate() {
+        assert_eq!(subresource.image_index, 0);
+        assert_eq!(subresource.level_index, level as u32);
+        assert_eq!(subresource.offset % 512, 0);
+        assert_eq!(subresource.row_pitch % 256, 0);
+        assert!(subresource.row_pitch >= ((subresource.width as usize + 3) / 4) * 8);
+        assert_eq!(subresource.rows, (subresource.height + 3) / 4);
+        assert_eq!(subresource.row_length() as usize, subresource.row_pitch / 8 * 4);
+    }
+
+    let mut staging = vec![0; layout.size];
+    prepared
+        .transcode_into_layout(&layout, DecodeFlags::empty(), &mut staging)
+        .unwrap();
+
+    // Every row must match the tightly packed output.
+    for subresource in &layout.subresources {
+        let tight = prepared
+            .transcode_image_level(0, subresource.level_index, format, DecodeFlags::empty())
+            .unwrap();
+        let tight_pitch = tight.len() / subresource.rows as usize;
+        for row in 0..subresource.rows as usize {
+            let start = subresource.offset + row * subresource.row_pitch;
+            assert_eq!(
+                &staging[start..start + tight_pitch],
+                &tight[row * tight_pitch..(row + 1) * tight_pitch]
+            );
+        }
+    }
+}
+
+#[test]
+fn staging_layout_rejects_zero_alignment() {
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+
+    let transcoder = basis::Transcoder::new();
+    let prepared = transcoder.prepare_transcoding(&image).unwrap();
+
+    assert_eq!(
+        StagingLayoutBuilder::new()
+            .row_pitch_alignment(0)
+            .build(&prepared, TargetTextureFormat::Bc1Rgb),
+        Err(BasisError::InvalidParameter("row_pitch_alignment"))
+    );
+    assert_eq!(
+        StagingLayoutBuilder::new()
+            .subresource_alignment(0)
+            .build(&prepared, TargetTextureFormat::Bc1Rgb),
+        Err(BasisError::InvalidParameter("subresource_alignment"))
+    );
+}
@@ -1,13 +1,24 @@
-use basis::TranscodeError;
+mod common;
 
+use basis::{BasisError, DecodeFlags};
+use common::crc16;
+use std::time::Duration;
+
+#[allow(clippy::manual_div_ceil)] // msrv doesn't allow this
 fn transcode(path: &str) {
     let image = std::fs::read(path).unwrap();
 
     let transcoder = basis::Transcoder::new();
 
-    assert_eq!(transcoder.get_total_images(&image).get(), 1);
-    assert_eq!(transcoder.get_total_image_levels(&image, 0), 12);
-    assert_eq!(transcoder.get_total_image_levels(&image, 1), 0);
+    assert_eq!(transcoder.get_total_images(&image).unwrap().get(), 1);
+    assert_eq!(transcoder.get_total_image_levels(&image, 0), Ok(12));
+    assert_eq!(
+        transcoder.get_total_image_levels(&image, 1),
+        Err(BasisError::ImageOutOfRange {
+            image_index: 1,
+            total_images: 1
+        })
+    );
     for i in 0..12 {
         let basic_level_info = transcoder.get_basic_image_level_info(&image, 0, i).unwrap();
         let size = 2048 >> i;
@@ -17,7 +28,14 @@ fn transcode(path: &str) {
         let blocks = blocks_size * blocks_size;
         assert_eq!(basic_level_info.total_blocks, blocks);
     }
-    assert_eq!(transcoder.get_basic_image_level_info(&image, 0, 12), None);
+    assert_eq!(
+        transcoder.get_basic_image_level_info(&image, 0, 12),
+        Err(BasisError::LevelOutOfRange {
+            image_index: 0,
+            level_index: 12,
+            total_levels: 12
+        })
+    );
 
     let file_format = transcoder.get_file_info(&image).unwrap().basis_format;
 
@@ -27,10 +45,10 @@ fn transcode(path: &str) {
         for mip in 0..12 {
             let format = unsafe { std::mem::transmute::<u8, basis::TargetTextureFormat>(format) };
 
-            let result = prepared.transcode_image_level(0, mip, format);
+            let result = prepared.transcode_image_level(0, mip, format, DecodeFlags::empty());
 
             match result {
-                Err(TranscodeError::OtherError) => panic!("Unknown error!"),
+                Err(BasisError::TranscodeFailed) => panic!("Unknown error!"),
                 Err(e) => println!("Not transcoding because {}", e),
                 Ok(_) => println!("Transcoding {:?} to {:?} mip {}", file_format, format, mip),
             }
@@ -47,3 +65,857 @@ fn load_transcode_etc1s() {
 fn load_transcode_uastc() {
     transcode(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_uastc.basis"));
 }
+
+#[test]
+fn transcode_into_buffer() {
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&image).unwrap();
+
+    let format = basis::TargetTextureFormat::Bc1Rgb;
+    let size = prepared.transcoded_size(0, 0, format).unwrap();
+    assert_eq!(size, 512 * 512 * 8);
+
+    let mut small = vec![0; size - 1];
+    match prepared.transcode_image_level_into(0, 0, format, DecodeFlags::empty(), &mut small) {
+        Err(BasisError::BufferTooSmall { required, provided }) => {
+            assert_eq!(required, size);
+            assert_eq!(provided, size - 1);
+        }
+        r => panic!("Unexpected result {:?}", r),
+    }
+
+    let mut buffer = vec![0; size + 16];
+    let written = prepared
+        .transcode_image_level_into(0, 0, format, DecodeFlags::empty(), &mut buffer)
+        .unwrap();
+    assert_eq!(written, size);
+    assert_eq!(
+        &buffer[..size],
+        &prepared
+            .transcode_image_level(0, 0, format, DecodeFlags::empty())
+            .unwrap()[..]
+    );
+
+    let mut uninit = vec![std::mem::MaybeUninit::uninit(); size];
+    let written = prepared
+        .transcode_image_level_into_uninit(0, 0, format, DecodeFlags::empty(), &mut uninit)
+        .unwrap();
+    assert_eq!(written, size);
+}
+
+#[test]
+fn transcode_with_decode_flags() {
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&image).unwrap();
+
+    let mut flags = DecodeFlags::BC1_FORBID_THREE_COLOR_BLOCKS | DecodeFlags::HIGH_QUALITY;
+    assert!(flags.contains(DecodeFlags::HIGH_QUALITY));
+    flags.remove(DecodeFlags::HIGH_QUALITY);
+    assert_eq!(flags, DecodeFlags::BC1_FORBID_THREE_COLOR_BLOCKS);
+
+    for &format in &[
+        basis::TargetTextureFormat::Bc1Rgb,
+        basis::TargetTextureFormat::Etc1Rgb,
+        basis::TargetTextureFormat::Rgba32,
+    ] {
+        prepared.transcode_image_level(0, 0, format, flags).unwrap();
+    }
+}
+
+#[test]
+fn transcode_slices() {
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&image).unwrap();
+
+    let slice = prepared.find_slice(0, 0, false).unwrap().unwrap();
+    let info = transcoder.get_file_info(&image).unwrap().slice_info[slice as usize].clone();
+    assert_eq!(info.image_index, 0);
+    assert_eq!(info.level_index, 0);
+    assert_eq!(
+        prepared.find_slice(0, 12, false),
+        Err(BasisError::LevelOutOfRange {
+            image_index: 0,
+            level_index: 12,
+            total_levels: 12
+        })
+    );
+
+    let level = prepared
+        .transcode_image_level(0, 0, basis::TargetTextureFormat::Bc1Rgb, DecodeFlags::empty())
+        .unwrap();
+
+    let mut tight = vec![0; level.len()];
+    let options = basis::SliceTranscodeOptions::default();
+    let written = prepared
+        .transcode_slice(slice, basis::BlockFormat::Bc1, &options, &mut tight, None)
+        .unwrap();
+    assert_eq!(written, level.len());
+    assert_eq!(tight, level);
+
+    // Leave every other 8 bytes untouched.
+    let mut interleaved = vec![0xFF; level.len() * 2];
+    let options = basis::SliceTranscodeOptions {
+        output_block_stride: Some(16),
+        ..Default::default()
+    };
+    prepared
+        .transcode_slice(slice, basis::BlockFormat::Bc1, &options, &mut interleaved, None)
+        .unwrap();
+    for (block, interleaved) in level.chunks(8).zip(interleaved.chunks(16)) {
+        assert_eq!(block, &interleaved[..8]);
+        assert!(interleaved[8..].iter().all(|&b| b == 0xFF));
+    }
+
+    #[allow(clippy::legacy_numeric_constants)] // msrv doesn't allow this
+    let options = basis::SliceTranscodeOptions {
+        row_pitch: u32::max_value(),
+        ..Default::default()
+    };
+    assert_eq!(
+        prepared.transcode_slice(slice, basis::BlockFormat::Bc1, &options, &mut tight, None),
+        Err(BasisError::OutputTooLarge)
+    );
+
+    let options = basis::SliceTranscodeOptions {
+        channel1: Some(4),
+        ..Default::default()
+    };
+    assert_eq!(
+        prepared.transcode_slice(slice, basis::BlockFormat::Bc5, &options, &mut tight, None),
+        Err(BasisError::InvalidChannel(4))
+    );
+}
+
+#[test]
+fn transcode_in_parallel() {
+    let image: &'static [u8] = Box::leak(
+        std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis"))
+            .unwrap()
+            .into_boxed_slice(),
+    );
+
+    let transcoder: &'static basis::Transcoder = Box::leak(Box::new(basis::Transcoder::new()));
+    let prepared: &'static basis::PreparedBasisFile<'static> =
+        Box::leak(Box::new(transcoder.prepare_transcoding(image).unwrap()));
+
+    let format = basis::TargetTextureFormat::Bc7Rgba;
+
+    let threads: Vec<_> = (0..12)
+        .map(|level| {
+            std::thread::spawn(move || {
+                let mut state = basis::TranscoderState::new();
+                prepared
+                    .transcode_image_level_with_state(&mut state, 0, level, format, DecodeFlags::empty())
+                    .unwrap()
+            })
+        })
+        .collect();
+
+    let mut state = basis::TranscoderState::new();
+    for (level, thread) in threads.into_iter().enumerate() {
+        let expected = prepared
+            .transcode_image_level_with_state(&mut state, 0, level as u32, format, DecodeFlags::empty())
+            .unwrap();
+        assert_eq!(thread.join().unwrap(), expected);
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn transcode_all_levels() {
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&image).unwrap();
+
+    let format = basis::TargetTextureFormat::Bc1Rgb;
+    let all = prepared.transcode_all(format, DecodeFlags::empty()).unwrap();
+
+    assert_eq!(all.len(), 12);
+    for (level, transcoded) in all.into_iter().enumerate() {
+        assert_eq!(transcoded.image_index, 0);
+        assert_eq!(transcoded.level_index, level as u32);
+        assert_eq!(
+            transcoded.data,
+            prepared
+                .transcode_image_level(0, level as u32, format, DecodeFlags::empty())
+                .unwrap()
+        );
+    }
+}
+
+/// Six 32x32 frames of a gradient moving right, 40ms apart.
+fn encode_video(basis_format: basis::BasisTextureFormat) -> Vec<u8> {
+    let frames: Vec<Vec<u8>> = (0..6u32)
+        .map(|frame| {
+            (0..32 * 32u32)
+                .flat_map(|pixel| {
+                    let (x, y) = (pixel % 32, pixel / 32);
+                    vec![
+                        ((x * 8 + frame * 20) % 256) as u8,
+                        (y * 8) as u8,
+                        (frame * 40) as u8,
+                        255,
+                    ]
+                })
+                .collect()
+        })
+        .collect();
+    let images: Vec<_> = frames
+        .iter()
+        .map(|pixels| basis::SourceImage::new(32, 32, pixels))
+        .collect();
+
+    basis::Compressor::new()
+        .basis_format(basis_format)
+        .texture_type(basis::TextureType::VideoFrames)
+        .us_per_frame(40_000)
+        .compress(&images)
+        .unwrap()
+        .data
+}
+
+#[test]
+fn video_decoder_iterates_frames() {
+    let file = encode_video(basis::BasisTextureFormat::Etc1s);
+
+    let transcoder = basis::Transcoder::new();
+    let prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let format = basis::TargetTextureFormat::Bc1Rgb;
+
+    let decoder = basis::VideoDecoder::new(&prepared, 0, format, DecodeFlags::empty()).unwrap();
+    assert_eq!(decoder.frame_count(), 6);
+    assert_eq!(decoder.frame_duration(), Duration::from_micros(40_000));
+    assert_eq!(decoder.duration(), Duration::from_micros(6 * 40_000));
+
+    let frames = decoder.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(frames.len(), 6);
+    for (frame_index, frame) in frames.iter().enumerate() {
+        assert_eq!(frame.frame_index, frame_index as u32);
+        assert_eq!(frame.timestamp, Duration::from_micros(frame_index as u64 * 40_000));
+        // ETC1S videos start with an I-frame, followed by P-frames.
+        assert_eq!(frame.iframe, frame_index == 0);
+        assert_eq!(frame.independent, frame_index == 0);
+    }
+
+    let mut decoder = basis::VideoDecoder::new(&prepared, 0, format, DecodeFlags::empty()).unwrap();
+    assert_eq!(
+        decoder.decode_frame(2),
+        Err(BasisError::OutOfOrderFrame {
+            requested: 2,
+            expected: 0
+        })
+    );
+    assert_eq!(decoder.decode_frame(0).unwrap().data, frames[0].data);
+    assert_eq!(
+        decoder.decode_frame(3),
+        Err(BasisError::OutOfOrderFrame {
+            requested: 3,
+            expected: 1
+        })
+    );
+    assert_eq!(decoder.decode_frame(1).unwrap().data, frames[1].data);
+}
+
+#[test]
+fn video_decoder_seeks() {
+    let file = encode_video(basis::BasisTextureFormat::Etc1s);
+
+    let transcoder = basis::Transcoder::new();
+    let prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let format = basis::TargetTextureFormat::Bc1Rgb;
+
+    let sequential: Vec<Vec<u8>> = basis::VideoDecoder::new(&prepared, 0, format, DecodeFlags::empty())
+        .unwrap()
+        .map(|frame| frame.unwrap().data)
+        .collect();
+
+    let mut decoder = basis::VideoDecoder::new(&prepared, 0, format, DecodeFlags::empty()).unwrap();
+    assert_eq!(decoder.previous_iframe(4), Some(0));
+
+    // Forwards from the I-frame, onwards from the current frame, and backwards through the I-frame again.
+    for &frame_index in &[2, 4, 5, 1, 3, 0] {
+        let frame = decoder.seek(frame_index).unwrap();
+        assert_eq!(frame.frame_index, frame_index);
+        assert_eq!(frame.data, sequential[frame_index as usize]);
+        assert_eq!(decoder.next_frame_index(), frame_index + 1);
+    }
+    assert_eq!(decoder.next().unwrap().unwrap().data, sequential[1]);
+
+    assert_eq!(decoder.frame_at(Duration::from_micros(90_000)), 2);
+    assert_eq!(
+        decoder.seek_to_time(Duration::from_micros(90_000)).unwrap().frame_index,
+        2
+    );
+    // Times past the end clamp to the last frame.
+    assert_eq!(decoder.frame_at(Duration::from_secs(60)), 5);
+    let last = decoder.seek_to_time(Duration::from_secs(60)).unwrap();
+    assert_eq!(last.frame_index, 5);
+    assert_eq!(last.data, sequential[5]);
+
+    assert_eq!(
+        decoder.seek(6),
+        Err(BasisError::FrameOutOfRange {
+            frame: 6,
+            frame_count: 6
+        })
+    );
+}
+
+#[test]
+fn uastc_video_seeks_to_any_frame() {
+    let file = encode_video(basis::BasisTextureFormat::UAstc);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let format = basis::TargetTextureFormat::Bc7Rgba;
+
+    let frames: Vec<Vec<u8>> = (0..6)
+        .map(|frame_index| {
+            prepared
+                .transcode_image_level(frame_index, 0, format, DecodeFlags::empty())
+                .unwrap()
+        })
+        .collect();
+
+    let mut decoder = basis::VideoDecoder::new(&prepared, 0, format, DecodeFlags::empty()).unwrap();
+    // UASTC videos don't flag I-frames, but every frame decodes on its own.
+    assert!((0..6).all(|frame_index| !decoder.is_iframe(frame_index) && decoder.is_independent(frame_index)));
+    assert_eq!(decoder.previous_iframe(4), None);
+    assert_eq!(decoder.previous_independent(4), Some(4));
+
+    for &frame_index in &[5, 1, 3] {
+        let frame = decoder.seek(frame_index).unwrap();
+        assert_eq!(frame.data, frames[frame_index as usize]);
+        assert!(!frame.iframe);
+        assert!(frame.independent);
+    }
+    assert_eq!(decoder.decode_frame(0).unwrap().data, frames[0]);
+}
+
+#[test]
+fn video_decoder_rejects_non_video() {
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+
+    let transcoder = basis::Transcoder::new();
+    let prepared = transcoder.prepare_transcoding(&image).unwrap();
+
+    match basis::VideoDecoder::new(&prepared, 0, basis::TargetTextureFormat::Bc1Rgb, DecodeFlags::empty()) {
+        Err(BasisError::NotVideo(basis::TextureType::D2)) => {}
+        Err(e) => panic!("Unexpected error {}", e),
+        Ok(_) => panic!("2D texture accepted as video"),
+    }
+}
+
+/// Rebuilds `etc/cat_etc1s.basis` as a file of `total_images` identical 16x16 images with `total_levels`
+/// levels each, by pointing new slice descriptors at the cat's smaller levels. Videos start with an
+/// I-frame and last 40ms per frame.
+fn repack_cat(texture_type: basis::TextureType, total_images: u32, total_levels: u32) -> Vec<u8> {
+    const SLICE_DESC_SIZE: usize = 23;
+    // The cat's 16x16 level.
+    const FIRST_LEVEL: usize = 7;
+
+    let mut file = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+    let header_size = u16::from_le_bytes([file[4], file[5]]) as usize;
+    let slice_descs_offset = u32::from_le_bytes([file[65], file[66], file[67], file[68]]) as usize;
+
+    let mut slice_descs = Vec::new();
+    for image_index in 0..total_images {
+        for level_index in 0..total_levels {
+            let offset = slice_descs_offset + (FIRST_LEVEL + level_index as usize) * SLICE_DESC_SIZE;
+            let mut slice_desc = file[offset..offset + SLICE_DESC_SIZE].to_vec();
+            slice_desc[..3].copy_from_slice(&image_index.to_le_bytes()[..3]);
+            slice_desc[3] = level_index as u8;
+            if texture_type == basis::TextureType::VideoFrames && image_index == 0 {
+                // cSliceDescFlagsFrameIsIFrame
+                slice_desc[4] |= 2;
+            }
+            slice_descs.extend(slice_desc);
+        }
+    }
+
+    // The new descriptors go at the end, so every other offset in the header stays valid.
+    let new_slice_descs_offset = file.len() as u32;
+    file.extend(slice_descs);
+
+    file[14..17].copy_from_slice(&(total_images * total_levels).to_le_bytes()[..3]);
+    file[17..20].copy_from_slice(&total_images.to_le_bytes()[..3]);
+    file[23] = texture_type as u8;
+    if texture_type == basis::TextureType::VideoFrames {
+        file[24..27].copy_from_slice(&40_000u32.to_le_bytes()[..3]);
+    }
+    file[65..69].copy_from_slice(&new_slice_descs_offset.to_le_bytes());
+
+    let data_size = (file.len() - header_size) as u32;
+    file[8..12].copy_from_slice(&data_size.to_le_bytes());
+    let data_crc = crc16(&file[header_size..]);
+    file[12..14].copy_from_slice(&data_crc.to_le_bytes());
+    let header_crc = crc16(&file[8..header_size]);
+    file[6..8].copy_from_slice(&header_crc.to_le_bytes());
+
+    file
+}
+
+#[test]
+fn video_decoder_orders_frames() {
+    let file = repack_cat(basis::TextureType::VideoFrames, 4, 1);
+
+    let transcoder = basis::Transcoder::new();
+    assert_eq!(transcoder.validate_file_checksums(&file, true), Ok(()));
+    let prepared = transcoder.prepare_transcoding(&file).unwrap();
+    let format = basis::TargetTextureFormat::Bc1Rgb;
+
+    let decoder = basis::VideoDecoder::new(&prepared, 0, format, DecodeFlags::empty()).unwrap();
+    assert_eq!(decoder.frame_count(), 4);
+    assert_eq!(decoder.frame_duration(), Duration::from_micros(40_000));
+    assert_eq!(decoder.duration(), Duration::from_micros(4 * 40_000));
+
+    let frames = decoder.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(frames.len(), 4);
+    for (frame_index, frame) in frames.iter().enumerate() {
+        assert_eq!(frame.frame_index, frame_index as u32);
+        assert_eq!(frame.timestamp, Duration::from_micros(frame_index as u64 * 40_000));
+        assert_eq!(frame.iframe, frame_index == 0);
+        assert_eq!(frame.independent, frame_index == 0);
+        assert_eq!(frame.data.len(), 4 * 4 * 8);
+    }
+
+    let mut decoder = basis::VideoDecoder::new(&prepared, 0, format, DecodeFlags::empty()).unwrap();
+    assert_eq!(
+        decoder.decode_frame(2),
+        Err(BasisError::OutOfOrderFrame {
+            requested: 2,
+            expected: 0
+        })
+    );
+    assert_eq!(decoder.decode_frame(0).unwrap().data, frames[0].data);
+    assert_eq!(decoder.decode_frame(1).unwrap().data, frames[1].data);
+    // I-frames can be decoded at any time.
+    assert_eq!(decoder.decode_frame(0).unwrap().data, frames[0].data);
+}
+
+#[test]
+fn video_decoder_rejects_etc1s_video_without_first_iframe() {
+    let mut file = repack_cat(basis::TextureType::VideoFrames, 4, 1);
+
+    // Clear the first frame's I-frame flag.
+    let header_size = u16::from_le_bytes([file[4], file[5]]) as usize;
+    let slice_descs_offset = u32::from_le_bytes([file[65], file[66], file[67], file[68]]) as usize;
+    file[slice_descs_offset + 4] &= !2;
+    let data_crc = crc16(&file[header_size..]);
+    file[12..14].copy_from_slice(&data_crc.to_le_bytes());
+    let header_crc = crc16(&file[8..header_size]);
+    file[6..8].copy_from_slice(&header_crc.to_le_bytes());
+
+    let transcoder = basis::Transcoder::new();
+    assert_eq!(transcoder.validate_file_checksums(&file, true), Ok(()));
+    let prepared = transcoder.prepare_transcoding(&file).unwrap();
+
+    match basis::VideoDecoder::new(&prepared, 0, basis::TargetTextureFormat::Bc1Rgb, DecodeFlags::empty()) {
+        Err(BasisError::InvalidFile) => {}
+        Err(e) => panic!("Unexpected error {}", e),
+        Ok(_) => panic!("ETC1S video without a first I-frame accepted"),
+    }
+}
+
+#[test]
+fn structured_errors() {
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+
+    let transcoder = basis::Transcoder::new();
+
+    assert_eq!(transcoder.validate_header(&image), Ok(()));
+    assert_eq!(transcoder.validate_file_checksums(&image, true), Ok(()));
+    assert_eq!(transcoder.validate_header(&image[..8]), Err(BasisError::InvalidHeader));
+    assert_eq!(transcoder.get_file_info(&[0; 128]), Err(BasisError::InvalidHeader));
+
+    let mut corrupted = image.clone();
+    let last = corrupted.len() - 1;
+    corrupted[last] ^= 0xFF;
+    assert_eq!(
+        transcoder.validate_file_checksums(&corrupted, true),
+        Err(BasisError::ChecksumMismatch)
+    );
+
+    let mut prepared = transcoder.prepare_transcoding(&image).unwrap();
+    assert_eq!(
+        transcoder.prepare_transcoding(&image).err(),
+        Some(BasisError::TranscoderBusy)
+    );
+
+    assert_eq!(
+        prepared.transcode_image_level(2, 0, basis::TargetTextureFormat::Bc1Rgb, DecodeFlags::empty()),
+        Err(BasisError::ImageOutOfRange {
+            image_index: 2,
+            total_images: 1
+        })
+    );
+    assert_eq!(
+        prepared.transcode_image_level(0, 0, basis::TargetTextureFormat::Rgba4444, DecodeFlags::empty()),
+        Err(BasisError::UnsupportedTarget {
+            basis_format: basis::BasisTextureFormat::Etc1s,
+            target: basis::TargetTextureFormat::Rgba4444,
+        })
+    );
+
+    drop(prepared);
+    assert!(transcoder.prepare_transcoding(&image).is_ok());
+}
+
+#[test]
+fn cubemap_faces() {
+    assert_eq!(basis::CubeFace::NegativeY.image_index(2), 15);
+    for (index, face) in basis::CubeFace::ALL.iter().enumerate() {
+        assert_eq!(face.index(), index as u32);
+    }
+
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&image).unwrap();
+
+    assert_eq!(
+        prepared.transcode_cube(0, 0, basis::TargetTextureFormat::Bc1Rgb, DecodeFlags::empty()),
+        Err(BasisError::UnsupportedTextureType(basis::TextureType::D2))
+    );
+}
+
+fn face_color(image_index: u32) -> [u8; 4] {
+    [(image_index * 20) as u8, 255 - (image_index * 20) as u8, 128, 255]
+}
+
+/// `count` solid 16x16 faces, each with its own color.
+fn encode_cubemap(count: u32) -> Vec<u8> {
+    let faces: Vec<Vec<u8>> = (0..count)
+        .map(|image_index| {
+            face_color(image_index)
+                .iter()
+                .cycle()
+                .take(16 * 16 * 4)
+                .cloned()
+                .collect()
+        })
+        .collect();
+    let images: Vec<_> = faces
+        .iter()
+        .map(|pixels| basis::SourceImage::new(16, 16, pixels))
+        .collect();
+
+    basis::Compressor::new()
+        .basis_format(basis::BasisTextureFormat::UAstc)
+        .texture_type(basis::TextureType::CubemapArray)
+        .compress(&images)
+        .unwrap()
+        .data
+}
+
+#[test]
+fn transcode_single_cube() {
+    let file = encode_cubemap(6);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    assert_eq!(prepared.cube_count(), Ok(1));
+
+    let format = basis::TargetTextureFormat::Rgba32;
+    let cube = prepared.transcode_cube(0, 0, format, DecodeFlags::empty()).unwrap();
+    assert_eq!((cube.width, cube.height), (16, 16));
+    assert_eq!(cube.layer_count, 6);
+    assert_eq!(cube.layer_stride, 16 * 16 * 4);
+    assert_eq!(cube.data.len(), 6 * cube.layer_stride);
+
+    for &face in &basis::CubeFace::ALL {
+        let data = cube.face(0, face).unwrap();
+        let expected = prepared
+            .transcode_image_level(face.index(), 0, format, DecodeFlags::empty())
+            .unwrap();
+        assert_eq!(data, &expected[..]);
+        // Faces come out in the order they were encoded in.
+        let color = face_color(face.index());
+        assert!(data[..4]
+            .iter()
+            .zip(&color)
+            .all(|(&a, &b)| (a as i32 - b as i32).abs() <= 8));
+    }
+
+    assert_eq!(
+        cube.face(1, basis::CubeFace::NegativeX),
+        Err(BasisError::FaceOutOfRange {
+            face_index: 7,
+            total_faces: 6
+        })
+    );
+    assert_eq!(prepared.transcode_cube_array(0, format, DecodeFlags::empty()), Ok(cube));
+}
+
+#[test]
+fn transcode_cube_array() {
+    let file = encode_cubemap(12);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    assert_eq!(prepared.cube_count(), Ok(2));
+
+    let format = basis::TargetTextureFormat::Bc7Rgba;
+    let array = prepared.transcode_cube_array(0, format, DecodeFlags::empty()).unwrap();
+    assert_eq!(array.layer_count, 12);
+    assert_eq!(array.layer_stride, 4 * 4 * 16);
+    assert_eq!(array.data.len(), 12 * array.layer_stride);
+
+    for image_index in 0..12 {
+        let expected = prepared
+            .transcode_image_level(image_index, 0, format, DecodeFlags::empty())
+            .unwrap();
+        let start = image_index as usize * array.layer_stride;
+        assert_eq!(&array.data[start..start + array.layer_stride], &expected[..]);
+    }
+    assert_eq!(
+        array.face(1, basis::CubeFace::PositiveY).unwrap(),
+        &array.data[8 * array.layer_stride..9 * array.layer_stride]
+    );
+
+    let second = prepared.transcode_cube(1, 0, format, DecodeFlags::empty()).unwrap();
+    assert_eq!(second.layer_count, 6);
+    assert_eq!(&second.data[..], &array.data[6 * array.layer_stride..]);
+    assert_eq!(
+        second.face(0, basis::CubeFace::NegativeZ).unwrap(),
+        array.face(1, basis::CubeFace::NegativeZ).unwrap()
+    );
+
+    assert_eq!(
+        prepared.transcode_cube(2, 0, format, DecodeFlags::empty()),
+        Err(BasisError::ImageOutOfRange {
+            image_index: 12,
+            total_images: 12
+        })
+    );
+}
+
+/// `count` 16x16 images of a gradient with a different blue channel in each, with mipmaps.
+fn encode_layers(texture_type: basis::TextureType, count: u32) -> Vec<u8> {
+    let layers: Vec<Vec<u8>> = (0..count)
+        .map(|layer| {
+            (0..16 * 16u32)
+                .flat_map(|pixel| {
+                    vec![
+                        (pixel % 16 * 16) as u8,
+                        (pixel / 16 * 16) as u8,
+                        (layer * 60) as u8,
+                        255,
+                    ]
+                })
+                .collect()
+        })
+        .collect();
+    let images: Vec<_> = layers
+        .iter()
+        .map(|pixels| basis::SourceImage::new(16, 16, pixels))
+        .collect();
+
+    basis::Compressor::new()
+        .texture_type(texture_type)
+        .mipmaps(Some(basis::MipmapOptions::default()))
+        .compress(&images)
+        .unwrap()
+        .data
+}
+
+/// Changes the texture type in a `.basis` file's header, keeping the header checksum valid.
+fn set_texture_type(file: &mut [u8], texture_type: basis::TextureType) {
+    let header_size = u16::from_le_bytes([file[4], file[5]]) as usize;
+    file[23] = texture_type as u8;
+    let crc = crc16(&file[8..header_size]);
+    file[6..8].copy_from_slice(&crc.to_le_bytes());
+}
+
+fn check_layered_level(texture_type: basis::TextureType, count: u32) {
+    let file = encode_layers(texture_type, count);
+
+    let transcoder = basis::Transcoder::new();
+    assert_eq!(transcoder.validate_file_checksums(&file, true), Ok(()));
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+
+    let format = basis::TargetTextureFormat::Bc1Rgb;
+    for level_index in 0..2 {
+        let level = prepared
+            .transcode_layered_level(level_index, format, DecodeFlags::empty())
+            .unwrap();
+        let size = 16 >> level_index;
+        assert_eq!(level.level_index, level_index);
+        assert_eq!((level.width, level.height), (size, size));
+        assert_eq!(level.layer_count, count);
+        assert_eq!(level.layer_stride, (size as usize / 4) * (size as usize / 4) * 8);
+        assert_eq!(level.data.len(), count as usize * level.layer_stride);
+
+        for layer in 0..count {
+            let expected = prepared
+                .transcode_image_level(layer, level_index, format, DecodeFlags::empty())
+                .unwrap();
+            assert_eq!(level.layer(layer).unwrap(), &expected[..]);
+        }
+        assert_eq!(
+            level.layer(count),
+            Err(BasisError::LayerOutOfRange {
+                layer_index: count,
+                total_layers: count
+            })
+        );
+    }
+}
+
+#[test]
+fn layered_level_of_array() {
+    check_layered_level(basis::TextureType::D2Array, 3);
+}
+
+#[test]
+fn layered_level_of_volume() {
+    check_layered_level(basis::TextureType::D3, 4);
+}
+
+#[test]
+fn layered_level_rejects_inconsistent_images() {
+    let pixels = vec![100; 16 * 16 * 4];
+    let mut file = basis::Compressor::new()
+        .compress(&[
+            basis::SourceImage::new(16, 16, &pixels),
+            basis::SourceImage::new(8, 8, &pixels[..8 * 8 * 4]),
+        ])
+        .unwrap()
+        .data;
+    // The compressor refuses to write such an array, but other encoders might.
+    set_texture_type(&mut file, basis::TextureType::D2Array);
+
+    let transcoder = basis::Transcoder::new();
+    assert_eq!(transcoder.get_texture_type(&file), Ok(basis::TextureType::D2Array));
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+
+    assert_eq!(
+        prepared.transcode_layered_level(0, basis::TargetTextureFormat::Bc1Rgb, DecodeFlags::empty()),
+        Err(BasisError::InconsistentImages { image_index: 1 })
+    );
+}
+
+#[test]
+fn transcode_cube_array_faces() {
+    let file = repack_cat(basis::TextureType::CubemapArray, 12, 2);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+    assert_eq!(prepared.cube_count(), Ok(2));
+
+    let format = basis::TargetTextureFormat::Bc1Rgb;
+    for level_index in 0..2 {
+        let array = prepared
+            .transcode_cube_array(level_index, format, DecodeFlags::empty())
+            .unwrap();
+        let size = 16 >> level_index;
+        assert_eq!((array.width, array.height), (size, size));
+        assert_eq!(array.layer_count, 12);
+        assert_eq!(array.layer_stride, (size as usize / 4) * (size as usize / 4) * 8);
+        assert_eq!(array.data.len(), 12 * array.layer_stride);
+
+        for cube in 0..2 {
+            for &face in &basis::CubeFace::ALL {
+                let expected = prepared
+                    .transcode_image_level(face.image_index(cube), level_index, format, DecodeFlags::empty())
+                    .unwrap();
+                assert_eq!(array.face(cube, face).unwrap(), &expected[..]);
+            }
+        }
+        assert_eq!(
+            array.face(2, basis::CubeFace::NegativeX),
+            Err(BasisError::FaceOutOfRange {
+                face_index: 13,
+                total_faces: 12
+            })
+        );
+
+        let second = prepared
+            .transcode_cube(1, level_index, format, DecodeFlags::empty())
+            .unwrap();
+        assert_eq!(second.layer_count, 6);
+        assert_eq!(&second.data[..], &array.data[6 * array.layer_stride..]);
+    }
+
+    assert_eq!(
+        prepared.transcode_cube(2, 0, format, DecodeFlags::empty()),
+        Err(BasisError::ImageOutOfRange {
+            image_index: 12,
+            total_images: 12
+        })
+    );
+}
+
+#[test]
+fn layered_level_requires_array_or_volume() {
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&image).unwrap();
+
+    assert_eq!(
+        prepared.transcode_layered_level(0, basis::TargetTextureFormat::Bc1Rgb, DecodeFlags::empty()),
+        Err(BasisError::UnsupportedTextureType(basis::TextureType::D2))
+    );
+}
+
+fn check_repacked_layered_level(texture_type: basis::TextureType, count: u32) {
+    let file = repack_cat(texture_type, count, 2);
+
+    let transcoder = basis::Transcoder::new();
+    let mut prepared = transcoder.prepare_transcoding(&file).unwrap();
+
+    let format = basis::TargetTextureFormat::Bc1Rgb;
+    for level_index in 0..2 {
+        let level = prepared
+            .transcode_layered_level(level_index, format, DecodeFlags::empty())
+            .unwrap();
+        let size = 16 >> level_index;
+        assert_eq!(level.level_index, level_index);
+        assert_eq!((level.width, level.height), (size, size));
+        assert_eq!(level.layer_count, count);
+        assert_eq!(level.layer_stride, (size as usize / 4) * (size as usize / 4) * 8);
+        assert_eq!(level.data.len(), count as usize * level.layer_stride);
+
+        for layer in 0..count {
+            let expected = prepared
+                .transcode_image_level(layer, level_index, format, DecodeFlags::empty())
+                .unwrap();
+            assert_eq!(level.layer(layer).unwrap(), &expected[..]);
+        }
+        assert_eq!(
+            level.layer(count),
+            Err(BasisError::LayerOutOfRange {
+                layer_index: count,
+                total_layers: count
+            })
+        );
+    }
+}
+
+#[test]
+fn layered_level_of_repacked_array() {
+    check_repacked_layered_level(basis::TextureType::D2Array, 3);
+}
+
+#[test]
+fn layered_level_of_repacked_volume() {
+    check_repacked_layered_level(basis::TextureType::D3, 4);
+}
+
+#[test]
+fn ktx2_rejects_basis_file() {
+    let image = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../etc/cat_etc1s.basis")).unwrap();
+
+    assert_eq!(
+        basis::Ktx2Transcoder::new(&image).err(),
+        Some(BasisError::InvalidHeader)
+    );
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "wgpu")]
+
+use basis::{
+    wgpu_types::{Features, TextureDimension, TextureFormat},
+    BasisError, BasisTextureFormat, Compressor, MipmapOptions, SourceImage, TargetTextureFormat, TextureType,
+};
+
+fn file_info(texture_type: TextureType, width: u32, height: u32, count: usize, mipmaps: bool) -> basis::FileInfo {
+    let pixels = vec![200; (width * height * 4) as usize];
+    let file = Compressor::new()
+        .texture_type(texture_type)
+        .mipmaps(if mipmaps { Some(MipmapOptions::default()) } else { None })
+        .compress(&vec![SourceImage::new(width, height, &pixels); count])
+        .unwrap()
+        .data;
+
+    basis::Transcoder::new().get_file_info(&file).unwrap()
+}
+
+#[test]
+fn wgpu_format() {
+    assert_eq!(
+        TargetTextureFormat::Bc7Rgba.wgpu_format(true),
+        Some(TextureFormat::Bc7RgbaUnormSrgb)
+    );
+    assert_eq!(
+        TargetTextureFormat::Bc7Rgba.wgpu_format(false),
+        Some(TextureFormat::Bc7RgbaUnorm)
+    );
+    assert_eq!(
+        TargetTextureFormat::Bc4R.wgpu_format(true),
+        Some(TextureFormat::Bc4RUnorm)
+    );
+    assert_eq!(TargetTextureFormat::Pvrtc1Rgba.wgpu_format(false), None);
+    assert_eq!(
+        TargetTextureFormat::AstcRgba.wgpu_features(),
+        Some(Features::TEXTURE_COMPRESSION_ASTC_LDR)
+    );
+    assert_eq!(TargetTextureFormat::Rgba32.wgpu_features(), Some(Features::empty()));
+}
+
+#[test]
+fn wgpu_texture_descriptor() {
+    let cubemap = file_info(TextureType::CubemapArray, 16, 16, 6, true);
+    let descriptor = cubemap
+        .wgpu_texture_descriptor(Some("sky"), TargetTextureFormat::Bc7Rgba, true)
+        .unwrap();
+    assert_eq!(descriptor.label, Some("sky"));
+    assert_eq!(
+        (descriptor.size.width, descriptor.size.height, descriptor.size.depth),
+        (16, 16, 6)
+    );
+    assert_eq!(descriptor.mip_level_count, 5);
+    assert_eq!(descriptor.dimension, TextureDimension::D2);
+    assert_eq!(descriptor.format, TextureFormat::Bc7RgbaUnormSrgb);
+
+    // Compressed formats are rounded up to whole blocks, uncompressed ones aren't.
+    let odd = file_info(TextureType::D3, 10, 6, 3, false);
+    let descriptor = odd
+        .wgpu_texture_descriptor(None, TargetTextureFormat::Bc1Rgb, false)
+        .unwrap();
+    assert_eq!(
+        (descriptor.size.width, descriptor.size.height, descriptor.size.depth),
+        (12, 8, 3)
+    );
+    assert_eq!(descriptor.dimension, TextureDimension::D3);
+    assert_eq!(descriptor.mip_level_count, 1);
+    let descriptor = odd
+        .wgpu_texture_descriptor(None, TargetTextureFormat::Rgba32, false)
+        .unwrap();
+    assert_eq!((descriptor.size.width, descriptor.size.height), (10, 6));
+
+    assert_eq!(
+        odd.wgpu_texture_descriptor(None, TargetTextureFormat::Fxt1Rgb, false),
+        Err(BasisError::UnsupportedOutputFormat(TargetTextureFormat::Fxt1Rgb))
+    );
+
+    // Every level of a basis volume has all of its slices, which a 3D texture's mips can't hold.
+    let mipmapped_volume = file_info(TextureType::D3, 16, 16, 3, true);
+    assert_eq!(
+        mipmapped_volume.wgpu_texture_descriptor(None, TargetTextureFormat::Bc1Rgb, false),
+        Err(BasisError::UnsupportedTextureType(TextureType::D3))
+    );
+}
+
+#[test]
+fn best_wgpu_target() {
+    let bc_and_astc = Features::TEXTURE_COMPRESSION_BC | Features::TEXTURE_COMPRESSION_ASTC_LDR;
+
+    assert_eq!(
+        BasisTextureFormat::UAstc.best_wgpu_target(bc_and_astc, true),
+        TargetTextureFormat::AstcRgba
+    );
+    assert_eq!(
+        BasisTextureFormat::Etc1s.best_wgpu_target(bc_and_astc, false),
+        TargetTextureFormat::Bc1Rgb
+    );
+    assert_eq!(
+        BasisTextureFormat::Etc1s.best_wgpu_target(Features::TEXTURE_COMPRESSION_ETC2, true),
+        TargetTextureFormat::Etc2Rgba
+    );
+    assert_eq!(
+        BasisTextureFormat::UAstc.best_wgpu_target(Features::empty(), false),
+        TargetTextureFormat::Rgba32
+    );
+}